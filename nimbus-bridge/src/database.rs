@@ -0,0 +1,276 @@
+use anyhow::{anyhow, bail};
+use sqlite3_parser::ast::{
+    CreateTableBody, Expr, FromClause, InsertBody, Literal, Name, QualifiedName, ResultColumn,
+    Select, SelectBody, SelectTable, SortOrder, SortedColumn,
+};
+use std::collections::HashMap;
+
+use crate::eval::eval;
+use crate::params::Params;
+use crate::value::{compare_values, is_truthy, Affinity, Value};
+
+#[derive(Debug, Clone)]
+struct Column {
+    name: String,
+    affinity: Affinity,
+}
+
+#[derive(Debug, Default, Clone)]
+struct Table {
+    columns: Vec<Column>,
+    rows: Vec<Vec<Value>>,
+}
+
+/// The in-memory tables `exec` reads and writes across a script, keyed by
+/// table name. A single `Database` is expected to live for as long as a
+/// caller wants its `CREATE TABLE`/`INSERT` statements to persist.
+#[derive(Debug, Default)]
+pub struct Database {
+    tables: HashMap<String, Table>,
+}
+
+fn table_name(qualified_name: &QualifiedName) -> String {
+    let QualifiedName { name: Name(name), .. } = qualified_name;
+    name.clone()
+}
+
+fn column_names(columns: &[Column]) -> Vec<String> {
+    columns.iter().map(|c| c.name.clone()).collect()
+}
+
+fn eval_where(
+    expr: &Expr,
+    row: &[Value],
+    columns: &[String],
+    params: &Params,
+) -> anyhow::Result<bool> {
+    Ok(is_truthy(&eval(expr, row, columns, params)?))
+}
+
+impl Database {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn create_table(
+        &mut self,
+        tbl_name: &QualifiedName,
+        body: &CreateTableBody,
+    ) -> anyhow::Result<()> {
+        let name = table_name(tbl_name);
+        let columns = match body {
+            CreateTableBody::ColumnsAndConstraints { columns, .. } => columns
+                .iter()
+                .map(|c| Column {
+                    name: c.col_name.0.clone(),
+                    affinity: c
+                        .col_type
+                        .as_ref()
+                        .map(|t| Affinity::from_type_name(&t.name))
+                        .unwrap_or(Affinity::Blob),
+                })
+                .collect::<Vec<_>>(),
+            CreateTableBody::AsSelect(_) => bail!("create-table-as-select not supported"),
+        };
+        self.tables.insert(name, Table { columns, rows: vec![] });
+        Ok(())
+    }
+
+    pub(crate) fn drop_table(
+        &mut self,
+        tbl_name: &QualifiedName,
+        if_exists: bool,
+    ) -> anyhow::Result<()> {
+        let name = table_name(tbl_name);
+        if self.tables.remove(&name).is_none() && !if_exists {
+            bail!("no such table: {name}");
+        }
+        Ok(())
+    }
+
+    pub(crate) fn insert(
+        &mut self,
+        tbl_name: &QualifiedName,
+        body: &InsertBody,
+        params: &Params,
+    ) -> anyhow::Result<()> {
+        let name = table_name(tbl_name);
+        let table = self
+            .tables
+            .get_mut(&name)
+            .ok_or_else(|| anyhow!("no such table: {name}"))?;
+
+        let InsertBody::Select(select, upsert) = body else {
+            bail!("insert-body-default-values not supported");
+        };
+        if upsert.is_some() {
+            bail!("insert-body-select-upsert not supported");
+        }
+        let Select {
+            with: None,
+            order_by: None,
+            limit: None,
+            body:
+                SelectBody {
+                    compounds: None,
+                    select: sqlite3_parser::ast::OneSelect::Values(values),
+                },
+        } = select
+        else {
+            bail!("insert-body-select not supported");
+        };
+
+        for row in values {
+            if row.len() != table.columns.len() {
+                bail!(
+                    "table {name} has {} columns but {} values were supplied",
+                    table.columns.len(),
+                    row.len()
+                );
+            }
+            let mut insert_row = vec![];
+            for (expr, column) in row.iter().zip(table.columns.iter()) {
+                match expr {
+                    Expr::Literal(_) | Expr::Variable(_) => {
+                        let value = eval(expr, &[], &[], params)?;
+                        insert_row.push(column.affinity.coerce(value));
+                    }
+                    _ => bail!("only literal or bound-parameter expressions supported"),
+                }
+            }
+            table.rows.push(insert_row);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn select(
+        &mut self,
+        select: &Select,
+        params: &Params,
+    ) -> anyhow::Result<Vec<Vec<Value>>> {
+        let Select {
+            with,
+            body,
+            order_by,
+            limit,
+        } = select;
+        if with.is_some() {
+            bail!("select-with not supported");
+        }
+        let SelectBody { select, compounds } = body;
+        if compounds.is_some() {
+            bail!("select-compounds not supported");
+        }
+        let sqlite3_parser::ast::OneSelect::Select {
+            distinctness,
+            columns,
+            from,
+            where_clause,
+            group_by,
+            window_clause,
+        } = select
+        else {
+            bail!("select-values not supported");
+        };
+        if distinctness.is_some() | group_by.is_some() | window_clause.is_some() {
+            bail!("select-(distinctness|group_by|window_clause) not supported");
+        }
+
+        let Some(from) = from else {
+            // No FROM clause: evaluate the column list once against an
+            // empty row context, e.g. `SELECT 1, 2, 3`.
+            if where_clause.is_some() || order_by.is_some() {
+                bail!("select-(where|order_by) without FROM not supported");
+            }
+            return project_row(columns, &[], &[], params).map(|row| vec![row]);
+        };
+        let FromClause { select: table_ref, joins, .. } = from;
+        if joins.is_some() {
+            bail!("select-joins not supported");
+        }
+        let table_ref = table_ref
+            .as_ref()
+            .ok_or_else(|| anyhow!("select without table"))?;
+        let SelectTable::Table(tbl_name, _, _) = table_ref.as_ref() else {
+            bail!("select-from-subquery not supported");
+        };
+        let name = table_name(tbl_name);
+        let table = self
+            .tables
+            .get(&name)
+            .ok_or_else(|| anyhow!("no such table: {name}"))?;
+
+        let names = column_names(&table.columns);
+
+        let mut rows = table.rows.clone();
+        if let Some(expr) = where_clause {
+            let mut filtered = vec![];
+            for row in rows {
+                if eval_where(expr, &row, &names, params)? {
+                    filtered.push(row);
+                }
+            }
+            rows = filtered;
+        }
+
+        if let Some(order_by) = order_by {
+            let mut keyed = Vec::with_capacity(rows.len());
+            for row in rows {
+                let keys = order_by
+                    .iter()
+                    .map(|SortedColumn { expr, .. }| eval(expr, &row, &names, params))
+                    .collect::<anyhow::Result<Vec<_>>>()?;
+                keyed.push((keys, row));
+            }
+            keyed.sort_by(|(a_keys, _), (b_keys, _)| {
+                for (i, SortedColumn { order, .. }) in order_by.iter().enumerate() {
+                    let ordering = compare_values(&a_keys[i], &b_keys[i]);
+                    let ordering = if *order == Some(SortOrder::Desc) {
+                        ordering.reverse()
+                    } else {
+                        ordering
+                    };
+                    if !ordering.is_eq() {
+                        return ordering;
+                    }
+                }
+                std::cmp::Ordering::Equal
+            });
+            rows = keyed.into_iter().map(|(_, row)| row).collect();
+        }
+
+        let projected = rows
+            .iter()
+            .map(|row| project_row(columns, row, &names, params))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let projected = if let Some(limit) = limit {
+            let n = match &limit.expr {
+                Expr::Literal(Literal::Numeric(n)) => n.parse::<usize>()?,
+                _ => bail!("only literal LIMIT supported"),
+            };
+            projected.into_iter().take(n).collect()
+        } else {
+            projected
+        };
+
+        Ok(projected)
+    }
+}
+
+fn project_row(
+    columns: &[ResultColumn],
+    row: &[Value],
+    table_columns: &[String],
+    params: &Params,
+) -> anyhow::Result<Vec<Value>> {
+    let mut projected = vec![];
+    for column in columns {
+        match column {
+            ResultColumn::Star => projected.extend(row.iter().cloned()),
+            ResultColumn::Expr(expr, _) => projected.push(eval(expr, row, table_columns, params)?),
+            _ => bail!("unsupported projected column: {column:?}"),
+        }
+    }
+    Ok(projected)
+}
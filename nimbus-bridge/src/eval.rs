@@ -0,0 +1,292 @@
+use crate::params::Params;
+use crate::value::{compare_values, is_truthy, Value};
+use anyhow::{bail, Context};
+use sqlite3_parser::ast::{Expr, Operator, UnaryOperator};
+
+/// Recursively evaluate `expr` against a single row, resolving
+/// `Expr::Id`/`Expr::Qualified` column references by name via `columns` and
+/// `Expr::Variable` placeholders via `params`. Column-less expressions
+/// (e.g. `1 + 2`) can be evaluated against an empty row and column list.
+pub(crate) fn eval(
+    expr: &Expr,
+    row: &[Value],
+    columns: &[String],
+    params: &Params,
+) -> anyhow::Result<Value> {
+    match expr {
+        Expr::Literal(literal) => Ok(Value::from_literal(literal)),
+        Expr::Id(id) => column(row, columns, &id.0),
+        Expr::Qualified(_, id) => column(row, columns, &id.0),
+        Expr::Variable(raw) => params.resolve(raw),
+        Expr::Parenthesized(exprs) if exprs.len() == 1 => eval(&exprs[0], row, columns, params),
+        Expr::Unary(op, inner) => eval_unary(*op, eval(inner, row, columns, params)?),
+        Expr::Binary(lhs, op, rhs) => eval_binary(
+            *op,
+            eval(lhs, row, columns, params)?,
+            eval(rhs, row, columns, params)?,
+        ),
+        Expr::FunctionCall { name, args, .. } => {
+            let args = args
+                .iter()
+                .flatten()
+                .map(|a| eval(a, row, columns, params))
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            eval_function(&name.0, &args)
+        }
+        Expr::Case {
+            base,
+            when_then_pairs,
+            else_expr,
+        } => eval_case(base, when_then_pairs, else_expr, row, columns, params),
+        _ => bail!("unsupported expression: {expr:?}"),
+    }
+}
+
+fn column(row: &[Value], columns: &[String], name: &str) -> anyhow::Result<Value> {
+    let idx = columns
+        .iter()
+        .position(|c| c.eq_ignore_ascii_case(name))
+        .with_context(|| format!("no such column: {name}"))?;
+    Ok(row[idx].clone())
+}
+
+fn eval_case(
+    base: &Option<Box<Expr>>,
+    when_then_pairs: &[(Expr, Expr)],
+    else_expr: &Option<Box<Expr>>,
+    row: &[Value],
+    columns: &[String],
+    params: &Params,
+) -> anyhow::Result<Value> {
+    let base_value = base
+        .as_ref()
+        .map(|e| eval(e, row, columns, params))
+        .transpose()?;
+    for (when, then) in when_then_pairs {
+        let when_value = eval(when, row, columns, params)?;
+        let matched = match &base_value {
+            Some(base_value) => compare_values(base_value, &when_value).is_eq(),
+            None => is_truthy(&when_value),
+        };
+        if matched {
+            return eval(then, row, columns, params);
+        }
+    }
+    match else_expr {
+        Some(expr) => eval(expr, row, columns, params),
+        None => Ok(Value::Null),
+    }
+}
+
+fn eval_unary(op: UnaryOperator, value: Value) -> anyhow::Result<Value> {
+    match op {
+        UnaryOperator::Negative => Ok(match value {
+            Value::Integer(i) => Value::Integer(-i),
+            Value::Real(r) => Value::Real(-r),
+            Value::Null => Value::Null,
+            _ => bail!("cannot negate non-numeric value"),
+        }),
+        UnaryOperator::Positive => Ok(value),
+        UnaryOperator::Not => Ok(match value {
+            Value::Null => Value::Null,
+            other => Value::Integer(!is_truthy(&other) as i64),
+        }),
+        _ => bail!("unsupported unary operator: {op:?}"),
+    }
+}
+
+fn as_f64(value: &Value) -> Option<f64> {
+    match value {
+        Value::Integer(i) => Some(*i as f64),
+        Value::Real(r) => Some(*r),
+        Value::Text(s) => s.parse::<f64>().ok(),
+        _ => None,
+    }
+}
+
+fn eval_binary(op: Operator, lhs: Value, rhs: Value) -> anyhow::Result<Value> {
+    match op {
+        Operator::And => Ok(match (lhs, rhs) {
+            (Value::Null, _) | (_, Value::Null) => Value::Null,
+            (lhs, rhs) => Value::Integer((is_truthy(&lhs) && is_truthy(&rhs)) as i64),
+        }),
+        Operator::Or => Ok(match (lhs, rhs) {
+            (lhs, _) if is_truthy(&lhs) => Value::Integer(1),
+            (_, rhs) if is_truthy(&rhs) => Value::Integer(1),
+            (Value::Null, _) | (_, Value::Null) => Value::Null,
+            _ => Value::Integer(0),
+        }),
+        Operator::Concat => Ok(match (lhs, rhs) {
+            (Value::Null, _) | (_, Value::Null) => Value::Null,
+            (lhs, rhs) => Value::Text(format!("{}{}", lhs.display(), rhs.display())),
+        }),
+        Operator::Equals
+        | Operator::NotEquals
+        | Operator::Less
+        | Operator::LessEquals
+        | Operator::Greater
+        | Operator::GreaterEquals => {
+            if matches!(lhs, Value::Null) || matches!(rhs, Value::Null) {
+                return Ok(Value::Null);
+            }
+            let ordering = compare_values(&lhs, &rhs);
+            let result = match op {
+                Operator::Equals => ordering.is_eq(),
+                Operator::NotEquals => ordering.is_ne(),
+                Operator::Less => ordering.is_lt(),
+                Operator::LessEquals => ordering.is_le(),
+                Operator::Greater => ordering.is_gt(),
+                Operator::GreaterEquals => ordering.is_ge(),
+                _ => unreachable!(),
+            };
+            Ok(Value::Integer(result as i64))
+        }
+        Operator::Add | Operator::Subtract | Operator::Multiply | Operator::Divide
+        | Operator::Modulus => {
+            if matches!(lhs, Value::Null) || matches!(rhs, Value::Null) {
+                return Ok(Value::Null);
+            }
+            arithmetic(op, lhs, rhs)
+        }
+        _ => bail!("unsupported binary operator: {op:?}"),
+    }
+}
+
+fn arithmetic(op: Operator, lhs: Value, rhs: Value) -> anyhow::Result<Value> {
+    if let (Value::Integer(a), Value::Integer(b)) = (&lhs, &rhs) {
+        let (a, b) = (*a, *b);
+        return Ok(match op {
+            Operator::Add => Value::Integer(a + b),
+            Operator::Subtract => Value::Integer(a - b),
+            Operator::Multiply => Value::Integer(a * b),
+            Operator::Divide => {
+                if b == 0 {
+                    Value::Null
+                } else {
+                    Value::Integer(a / b)
+                }
+            }
+            Operator::Modulus => {
+                if b == 0 {
+                    Value::Null
+                } else {
+                    Value::Integer(a % b)
+                }
+            }
+            _ => unreachable!(),
+        });
+    }
+
+    let a = as_f64(&lhs).with_context(|| format!("not a number: {lhs:?}"))?;
+    let b = as_f64(&rhs).with_context(|| format!("not a number: {rhs:?}"))?;
+    Ok(match op {
+        Operator::Add => Value::Real(a + b),
+        Operator::Subtract => Value::Real(a - b),
+        Operator::Multiply => Value::Real(a * b),
+        Operator::Divide => {
+            if b == 0.0 {
+                Value::Null
+            } else {
+                Value::Real(a / b)
+            }
+        }
+        Operator::Modulus => {
+            if b == 0.0 {
+                Value::Null
+            } else {
+                Value::Real(a % b)
+            }
+        }
+        _ => unreachable!(),
+    })
+}
+
+fn eval_function(name: &str, args: &[Value]) -> anyhow::Result<Value> {
+    match name.to_ascii_lowercase().as_str() {
+        "abs" => Ok(match args.first() {
+            Some(Value::Integer(i)) => Value::Integer(i.abs()),
+            Some(Value::Real(r)) => Value::Real(r.abs()),
+            Some(Value::Null) | None => Value::Null,
+            Some(other) => bail!("abs: not a number: {other:?}"),
+        }),
+        "length" => Ok(match args.first() {
+            Some(Value::Text(s)) => Value::Integer(s.chars().count() as i64),
+            Some(Value::Blob(b)) => Value::Integer(b.len() as i64),
+            Some(Value::Null) | None => Value::Null,
+            Some(other) => Value::Integer(other.display().chars().count() as i64),
+        }),
+        "lower" => Ok(Value::Text(
+            args.first().map(Value::display).unwrap_or_default().to_ascii_lowercase(),
+        )),
+        "upper" => Ok(Value::Text(
+            args.first().map(Value::display).unwrap_or_default().to_ascii_uppercase(),
+        )),
+        "coalesce" => Ok(args
+            .iter()
+            .find(|v| !matches!(v, Value::Null))
+            .cloned()
+            .unwrap_or(Value::Null)),
+        "round" => {
+            let value = args.first().and_then(as_f64).unwrap_or(0.0);
+            let digits = args.get(1).and_then(as_f64).unwrap_or(0.0) as i32;
+            let factor = 10f64.powi(digits);
+            Ok(Value::Real((value * factor).round() / factor))
+        }
+        "substr" | "substring" => {
+            let s = args.first().map(Value::display).unwrap_or_default();
+            let chars: Vec<char> = s.chars().collect();
+            let start = args.get(1).and_then(as_f64).unwrap_or(1.0) as i64;
+            let start_idx = if start > 0 { (start - 1) as usize } else { 0 };
+            let len = args
+                .get(2)
+                .and_then(as_f64)
+                .map(|n| n as usize)
+                .unwrap_or(chars.len().saturating_sub(start_idx));
+            Ok(Value::Text(
+                chars
+                    .into_iter()
+                    .skip(start_idx)
+                    .take(len)
+                    .collect::<String>(),
+            ))
+        }
+        "typeof" => Ok(Value::Text(
+            args.first().map(Value::type_name).unwrap_or("null").to_string(),
+        )),
+        "regexp" => {
+            let pattern = args.first().map(Value::display).unwrap_or_default();
+            let haystack = args.get(1).map(Value::display).unwrap_or_default();
+            let matched = regex_lite_contains(&pattern, &haystack)?;
+            Ok(Value::Integer(matched as i64))
+        }
+        _ => bail!("unknown function: {name}"),
+    }
+}
+
+/// A minimal pattern matcher backing SQLite's `regexp` operator without a
+/// full regex engine: reports whether `pattern` matches anywhere in
+/// `haystack`, where `.` matches any one character and a `*` followed by
+/// more pattern matches any run of characters (glob-style, not a repeat of
+/// the preceding character). A trailing `*` and every other character match
+/// literally.
+fn regex_lite_contains(pattern: &str, haystack: &str) -> anyhow::Result<bool> {
+    fn matches_at(pattern: &[char], text: &[char]) -> bool {
+        match pattern.first() {
+            None => true,
+            Some('*') if pattern.len() > 1 => {
+                let rest = &pattern[1..];
+                (0..=text.len()).any(|i| matches_at(rest, &text[i..]))
+                    || matches_at(rest, text)
+            }
+            Some(&p) => {
+                !text.is_empty()
+                    && (p == '.' || p == text[0])
+                    && matches_at(&pattern[1..], &text[1..])
+            }
+        }
+    }
+
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = haystack.chars().collect();
+    Ok((0..=text.len()).any(|i| matches_at(&pattern, &text[i..])))
+}
@@ -1,48 +1,78 @@
 use anyhow::bail;
 use fallible_iterator::FallibleIterator;
 use sqlite3_parser::ast::fmt::{ToTokens, TokenStream};
-use sqlite3_parser::ast::{Cmd, Literal, ResultColumn, Select, SelectBody, Stmt};
+use sqlite3_parser::ast::{Cmd, Stmt};
 use sqlite3_parser::dialect::TokenType;
 
-type ExecResult = anyhow::Result<Option<Vec<Vec<Literal>>>>;
+mod database;
+mod eval;
+mod params;
+mod slt;
+mod value;
 
-fn exec(sql: &str) -> ExecResult {
+pub use database::Database;
+pub use params::Params;
+pub use slt::{run_slt, RecordOutcome};
+pub use value::Value;
+
+type ExecResult = anyhow::Result<Option<Vec<Vec<Value>>>>;
+
+pub(crate) fn exec(sql: &str, db: &mut Database) -> ExecResult {
+    exec_with_params(sql, db, &[])
+}
+
+/// Like `exec`, but binds `?`, `?NNN`, and `:name`/`@name`/`$name`
+/// placeholders in the parsed statement to `params` before evaluation.
+pub fn exec_with_params(
+    sql: &str,
+    db: &mut Database,
+    params: &[(Option<&str>, Value)],
+) -> ExecResult {
+    let params = Params::from_pairs(params);
     let mut parser = sqlite3_parser::lexer::sql::Parser::new(sql.as_ref());
     Ok(Some(parser.try_fold(vec![], |mut rows, cmd| {
         if let Cmd::Stmt(stmt) = cmd {
-            if let Stmt::Select(Select {
-                with: None,
-                order_by: None,
-                limit: None,
-                body:
-                    SelectBody {
-                        compounds: None,
-                        select:
-                            sqlite3_parser::ast::OneSelect::Select {
-                                distinctness: None,
-                                from: None,
-                                where_clause: None,
-                                group_by: None,
-                                window_clause: None,
-                                columns,
-                            },
-                    },
-            }) = &stmt
-            {
-                let row = columns.iter().try_fold(vec![], |mut row, column| {
-                    if let ResultColumn::Expr(sqlite3_parser::ast::Expr::Literal(literal), None) =
-                        column
+            match stmt {
+                Stmt::CreateTable {
+                    ref tbl_name,
+                    ref body,
+                    ..
+                } => {
+                    db.create_table(tbl_name, body)?;
+                    Ok(rows)
+                }
+                Stmt::DropTable {
+                    ref tbl_name,
+                    if_exists,
+                } => {
+                    db.drop_table(tbl_name, if_exists)?;
+                    Ok(rows)
+                }
+                Stmt::Insert {
+                    ref with,
+                    ref or_conflict,
+                    ref tbl_name,
+                    ref columns,
+                    ref body,
+                    ref returning,
+                } => {
+                    if with.is_some()
+                        | or_conflict.is_some()
+                        | columns.is_some()
+                        | returning.is_some()
                     {
-                        row.push(literal.clone());
-                        Ok(row)
-                    } else {
-                        bail!("Unexpected column {:?}", column)
+                        bail!("insert-(with|or_conflict|columns|returning) not supported");
                     }
-                })?;
-                rows.push(row);
-                Ok(rows)
-            } else {
-                bail!("Unexpected stmt: {:?}", stmt);
+                    db.insert(tbl_name, body, &params)?;
+                    Ok(rows)
+                }
+                Stmt::Select(ref select) => {
+                    rows.append(&mut db.select(select, &params)?);
+                    Ok(rows)
+                }
+                _ => {
+                    bail!("Unexpected stmt: {:?}", stmt);
+                }
             }
         } else {
             bail!("Unexpected cmd: {:?}", cmd);
@@ -50,7 +80,7 @@ fn exec(sql: &str) -> ExecResult {
     })?))
 }
 
-struct TokenFormatter {
+pub(crate) struct TokenFormatter {
     result: String,
 }
 
@@ -64,7 +94,7 @@ impl TokenStream for TokenFormatter {
 }
 
 impl TokenFormatter {
-    fn format<Value: ToTokens>(value: &Value) -> String {
+    pub(crate) fn format<Tokens: ToTokens>(value: &Tokens) -> String {
         let mut token_formatter = TokenFormatter {
             result: String::new(),
         };
@@ -80,7 +110,7 @@ fn as_str(exec_result: &ExecResult) -> String {
             .iter()
             .map(|row| {
                 row.iter()
-                    .map(TokenFormatter::format)
+                    .map(Value::display)
                     .collect::<Vec<String>>()
                     .join("|")
             })
@@ -96,9 +126,80 @@ fn as_str(exec_result: &ExecResult) -> String {
 mod tests {
     use crate::as_str;
     use crate::exec;
+    use crate::exec_with_params;
+    use crate::Database;
+    use crate::Value;
 
     #[test]
     fn it_works() {
-        assert_eq!(as_str(&exec("select 1, 2, 3")), "1|2|3");
+        let mut db = Database::new();
+        assert_eq!(as_str(&exec("select 1, 2, 3", &mut db)), "1|2|3");
+    }
+
+    #[test]
+    fn select_from_table() {
+        let mut db = Database::new();
+        exec("create table test1(f1)", &mut db).unwrap();
+        exec("insert into test1 values (11)", &mut db).unwrap();
+        assert_eq!(
+            as_str(&exec("select f1 from test1", &mut db)),
+            "11"
+        );
+    }
+
+    #[test]
+    fn select_list_evaluates_expressions_and_functions() {
+        let mut db = Database::new();
+        assert_eq!(as_str(&exec("select 1+2", &mut db)), "3");
+        assert_eq!(as_str(&exec("select abs(-5)", &mut db)), "5");
+        assert_eq!(as_str(&exec("select upper('x')", &mut db)), "X");
+    }
+
+    #[test]
+    fn modulus_by_zero_is_null_for_integers_and_reals() {
+        let mut db = Database::new();
+        assert_eq!(as_str(&exec("select 5 % 0", &mut db)), "");
+        assert_eq!(as_str(&exec("select 1.5 % 0", &mut db)), "");
+    }
+
+    #[test]
+    fn select_order_by_sorts_rows() {
+        let mut db = Database::new();
+        exec("create table test1(f1 integer)", &mut db).unwrap();
+        exec("insert into test1 values (3)", &mut db).unwrap();
+        exec("insert into test1 values (1)", &mut db).unwrap();
+        exec("insert into test1 values (2)", &mut db).unwrap();
+        assert_eq!(
+            as_str(&exec("select f1 from test1 order by f1", &mut db)),
+            "1\n2\n3"
+        );
+    }
+
+    #[test]
+    fn select_order_by_propagates_eval_errors() {
+        let mut db = Database::new();
+        exec("create table test1(f1 integer)", &mut db).unwrap();
+        exec("insert into test1 values (1)", &mut db).unwrap();
+        assert!(exec("select f1 from test1 order by nosuchcolumn", &mut db).is_err());
+    }
+
+    #[test]
+    fn exec_with_params_binds_positional_and_named_placeholders() {
+        let mut db = Database::new();
+        exec("create table test1(f1 integer, f2 text)", &mut db).unwrap();
+        exec_with_params(
+            "insert into test1 values (?, :name)",
+            &mut db,
+            &[(None, Value::Integer(11)), (Some(":name"), Value::Text("a".into()))],
+        )
+        .unwrap();
+        assert_eq!(
+            as_str(&exec_with_params(
+                "select f1 from test1 where f2 = ?",
+                &mut db,
+                &[(None, Value::Text("a".into()))],
+            )),
+            "11"
+        );
     }
 }
@@ -0,0 +1,63 @@
+use crate::value::Value;
+use anyhow::anyhow;
+use std::cell::Cell;
+use std::collections::HashMap;
+
+/// Bound values for a statement's `?`, `?NNN`, and `:name`/`@name`/`$name`
+/// placeholders (the `Expr::Variable` nodes `sqlite3_parser` produces).
+/// A `None` name binds the next positional slot; a `Some(name)` binds a
+/// named placeholder, with or without its leading sigil.
+#[derive(Debug, Default)]
+pub struct Params {
+    positional: Vec<Value>,
+    named: HashMap<String, Value>,
+    next_auto_index: Cell<usize>,
+}
+
+impl Params {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_pairs(pairs: &[(Option<&str>, Value)]) -> Self {
+        let mut positional = vec![];
+        let mut named = HashMap::new();
+        for (name, value) in pairs {
+            match name {
+                Some(name) => {
+                    named.insert(name.trim_start_matches([':', '@', '$']).to_string(), value.clone());
+                }
+                None => positional.push(value.clone()),
+            }
+        }
+        Self {
+            positional,
+            named,
+            next_auto_index: Cell::new(0),
+        }
+    }
+
+    /// Resolve the raw text of an `Expr::Variable` (e.g. `?`, `?2`, `:name`)
+    /// to its bound value.
+    pub(crate) fn resolve(&self, raw: &str) -> anyhow::Result<Value> {
+        let rest = raw.trim_start_matches(['?', ':', '@', '$']);
+        if rest.is_empty() {
+            let idx = self.next_auto_index.get();
+            self.next_auto_index.set(idx + 1);
+            self.positional
+                .get(idx)
+                .cloned()
+                .ok_or_else(|| anyhow!("no value bound for parameter ?{}", idx + 1))
+        } else if let Ok(n) = rest.parse::<usize>() {
+            self.positional
+                .get(n.saturating_sub(1))
+                .cloned()
+                .ok_or_else(|| anyhow!("no value bound for parameter ?{n}"))
+        } else {
+            self.named
+                .get(rest)
+                .cloned()
+                .ok_or_else(|| anyhow!("no value bound for parameter {raw}"))
+        }
+    }
+}
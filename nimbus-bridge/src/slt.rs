@@ -0,0 +1,336 @@
+use crate::{exec, Value};
+use anyhow::{bail, Context};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ColumnType {
+    Text,
+    Integer,
+    Real,
+}
+
+impl TryFrom<char> for ColumnType {
+    type Error = anyhow::Error;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            'T' => Ok(ColumnType::Text),
+            'I' => Ok(ColumnType::Integer),
+            'R' => Ok(ColumnType::Real),
+            _ => bail!("unknown sqllogictest type char: {c}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SortMode {
+    NoSort,
+    RowSort,
+    ValueSort,
+}
+
+impl TryFrom<&str> for SortMode {
+    type Error = anyhow::Error;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        match s {
+            "nosort" => Ok(SortMode::NoSort),
+            "rowsort" => Ok(SortMode::RowSort),
+            "valuesort" => Ok(SortMode::ValueSort),
+            _ => bail!("unknown sqllogictest sort mode: {s}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Expected {
+    Rows(Vec<String>),
+    Hash { count: usize, hex: String },
+}
+
+#[derive(Debug)]
+enum SltRecord {
+    Statement {
+        expect_ok: bool,
+        sql: String,
+    },
+    Query {
+        types: Vec<ColumnType>,
+        sort_mode: SortMode,
+        #[allow(dead_code)]
+        label: Option<String>,
+        sql: String,
+        expected: Expected,
+    },
+}
+
+/// The outcome of replaying a single sqllogictest record against `exec`.
+#[derive(Debug)]
+pub struct RecordOutcome {
+    pub index: usize,
+    pub passed: bool,
+    pub detail: Option<String>,
+}
+
+fn parse_records(src: &str) -> anyhow::Result<Vec<SltRecord>> {
+    let lines: Vec<&str> = src.lines().collect();
+    let mut records = vec![];
+    let mut i = 0;
+
+    while i < lines.len() {
+        let line = lines[i].trim_end();
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            i += 1;
+            continue;
+        }
+
+        if let Some(rest) = line.strip_prefix("statement ") {
+            let expect_ok = match rest.trim() {
+                "ok" => true,
+                "error" => false,
+                other => bail!("unexpected statement directive: {other}"),
+            };
+            i += 1;
+            let mut sql_lines = vec![];
+            while i < lines.len() && !lines[i].trim().is_empty() {
+                sql_lines.push(lines[i]);
+                i += 1;
+            }
+            records.push(SltRecord::Statement {
+                expect_ok,
+                sql: sql_lines.join("\n"),
+            });
+        } else if let Some(rest) = line.strip_prefix("query ") {
+            let mut parts = rest.split_whitespace();
+            let type_string = parts.next().context("missing query type-string")?;
+            let sort_mode = parts.next().context("missing query sort-mode")?;
+            let label = parts.next().map(str::to_string);
+
+            let types = type_string
+                .chars()
+                .map(ColumnType::try_from)
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            let sort_mode = SortMode::try_from(sort_mode)?;
+
+            i += 1;
+            let mut sql_lines = vec![];
+            while i < lines.len() && lines[i].trim() != "----" {
+                sql_lines.push(lines[i]);
+                i += 1;
+            }
+            i += 1; // consume "----"
+
+            let mut expected_lines = vec![];
+            while i < lines.len() && !lines[i].trim().is_empty() {
+                expected_lines.push(lines[i].trim().to_string());
+                i += 1;
+            }
+
+            let expected = if expected_lines.len() == 1
+                && expected_lines[0].contains("values hashing to")
+            {
+                let mut it = expected_lines[0].split("values hashing to");
+                let count: usize = it
+                    .next()
+                    .context("missing hash count")?
+                    .trim()
+                    .parse()
+                    .context("invalid hash count")?;
+                let hex = it
+                    .next()
+                    .context("missing hash digest")?
+                    .trim()
+                    .to_string();
+                Expected::Hash { count, hex }
+            } else {
+                Expected::Rows(expected_lines)
+            };
+
+            records.push(SltRecord::Query {
+                types,
+                sort_mode,
+                label,
+                sql: sql_lines.join("\n"),
+                expected,
+            });
+        } else {
+            bail!("unexpected sqllogictest line: {line}");
+        }
+    }
+
+    Ok(records)
+}
+
+/// Render a cell the way sqllogictest does: the declared per-column type
+/// character dictates how the value is cast and stringified, regardless of
+/// the runtime `Value` variant nimbus actually produced for it.
+fn format_cell(value: &Value, column_type: ColumnType) -> String {
+    if matches!(value, Value::Null) {
+        return "NULL".to_string();
+    }
+    match column_type {
+        ColumnType::Text => {
+            let s = value.display();
+            if s.is_empty() {
+                "(empty)".to_string()
+            } else {
+                s
+            }
+        }
+        ColumnType::Integer => as_i64(value).to_string(),
+        ColumnType::Real => format!("{:.3}", as_f64(value)),
+    }
+}
+
+fn as_i64(value: &Value) -> i64 {
+    match value {
+        Value::Integer(i) => *i,
+        Value::Real(r) => *r as i64,
+        Value::Text(s) => s.trim().parse::<f64>().map(|f| f as i64).unwrap_or(0),
+        Value::Blob(_) | Value::Null => 0,
+    }
+}
+
+fn as_f64(value: &Value) -> f64 {
+    match value {
+        Value::Real(r) => *r,
+        Value::Integer(i) => *i as f64,
+        Value::Text(s) => s.trim().parse::<f64>().unwrap_or(0.0),
+        Value::Blob(_) | Value::Null => 0.0,
+    }
+}
+
+fn format_row(row: &[Value], types: &[ColumnType]) -> Vec<String> {
+    row.iter()
+        .zip(types.iter())
+        .map(|(value, column_type)| format_cell(value, *column_type))
+        .collect()
+}
+
+fn digest_hex(values: &[String]) -> String {
+    let mut joined = String::new();
+    for value in values {
+        joined.push_str(value);
+        joined.push('\n');
+    }
+    let digest = md5::compute(joined.as_bytes());
+    format!("{digest:x}")
+}
+
+fn check_query(
+    types: &[ColumnType],
+    sort_mode: SortMode,
+    expected: &Expected,
+    rows: Vec<Vec<Value>>,
+) -> bool {
+    let mut formatted: Vec<Vec<String>> = rows.iter().map(|row| format_row(row, types)).collect();
+
+    match sort_mode {
+        SortMode::NoSort => {}
+        SortMode::RowSort => {
+            let mut joined: Vec<String> = formatted.iter().map(|row| row.join("|")).collect();
+            joined.sort();
+            formatted = joined.into_iter().map(|row| vec![row]).collect();
+        }
+        SortMode::ValueSort => {
+            let mut values: Vec<String> = formatted.into_iter().flatten().collect();
+            values.sort();
+            formatted = values.into_iter().map(|value| vec![value]).collect();
+        }
+    }
+
+    let values: Vec<String> = formatted.into_iter().flatten().collect();
+
+    match expected {
+        Expected::Rows(expected_lines) => &values == expected_lines,
+        Expected::Hash { count, hex } => {
+            values.len() == *count && digest_hex(&values).eq_ignore_ascii_case(hex)
+        }
+    }
+}
+
+/// Replay a sqllogictest-format script against [`exec`], returning one
+/// [`RecordOutcome`] per `statement`/`query` record in source order.
+pub fn run_slt(src: &str) -> anyhow::Result<Vec<RecordOutcome>> {
+    let records = parse_records(src)?;
+    let mut outcomes = vec![];
+    let mut db = crate::Database::new();
+
+    for (index, record) in records.into_iter().enumerate() {
+        let (passed, detail) = match record {
+            SltRecord::Statement { expect_ok, sql } => match exec(&sql, &mut db) {
+                Ok(_) if expect_ok => (true, None),
+                Ok(_) => (false, Some("expected statement to fail, but it succeeded".into())),
+                Err(e) if expect_ok => (false, Some(format!("{e}"))),
+                Err(_) => (true, None),
+            },
+            SltRecord::Query {
+                types,
+                sort_mode,
+                sql,
+                expected,
+                ..
+            } => match exec(&sql, &mut db) {
+                Ok(Some(rows)) => {
+                    let passed = check_query(&types, sort_mode, &expected, rows);
+                    (passed, (!passed).then(|| "row mismatch".to_string()))
+                }
+                Ok(None) => (false, Some("query produced no result set".into())),
+                Err(e) => (false, Some(format!("{e}"))),
+            },
+        };
+        outcomes.push(RecordOutcome {
+            index,
+            passed,
+            detail,
+        });
+    }
+
+    Ok(outcomes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::run_slt;
+
+    #[test]
+    fn query_renders_cells_per_declared_column_type() {
+        let src = "\
+statement ok
+CREATE TABLE t1(a INTEGER)
+
+statement ok
+INSERT INTO t1 VALUES (3)
+
+query R nosort
+SELECT a FROM t1
+----
+3.000
+";
+        let outcomes = run_slt(src).unwrap();
+        assert_eq!(outcomes.len(), 3);
+        for outcome in &outcomes {
+            assert!(outcome.passed, "{:?}", outcome.detail);
+        }
+    }
+
+    #[test]
+    fn statement_and_query_records_share_one_database() {
+        let src = "\
+statement ok
+CREATE TABLE t1(a INTEGER)
+
+statement ok
+INSERT INTO t1 VALUES (1)
+
+query I nosort
+SELECT a FROM t1
+----
+1
+";
+        let outcomes = run_slt(src).unwrap();
+        assert_eq!(outcomes.len(), 3);
+        for outcome in &outcomes {
+            assert!(outcome.passed, "{:?}", outcome.detail);
+        }
+    }
+}
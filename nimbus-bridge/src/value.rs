@@ -0,0 +1,232 @@
+use sqlite3_parser::ast::Literal as AstLiteral;
+use std::cmp::Ordering;
+
+/// A SQLite dynamic value, mirroring the storage classes `rusqlite::types`
+/// exposes (`Null`/`Integer`/`Real`/`Text`/`Blob`).
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Integer(i64),
+    Real(f64),
+    Text(String),
+    Blob(Vec<u8>),
+}
+
+impl Value {
+    pub fn from_literal(literal: &AstLiteral) -> Self {
+        match literal {
+            AstLiteral::Null => Value::Null,
+            AstLiteral::Numeric(n) => parse_numeric(n),
+            AstLiteral::String(s) => Value::Text(unquote(s)),
+            AstLiteral::Blob(hex) => Value::Blob(decode_hex(hex)),
+            other => Value::Text(crate::TokenFormatter::format(other)),
+        }
+    }
+
+    /// The name SQLite's `typeof()` function reports for this value.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Null => "null",
+            Value::Integer(_) => "integer",
+            Value::Real(_) => "real",
+            Value::Text(_) => "text",
+            Value::Blob(_) => "blob",
+        }
+    }
+
+    /// Render a value the way SQLite's shell formats a result column:
+    /// integers without a trailing `.0`, reals in `%.15g`-ish form, and
+    /// NULL as the empty string.
+    pub fn display(&self) -> String {
+        match self {
+            Value::Null => String::new(),
+            Value::Integer(i) => i.to_string(),
+            Value::Real(r) => format_real(*r),
+            Value::Text(s) => s.clone(),
+            Value::Blob(b) => format!("x'{}'", hex_encode(b)),
+        }
+    }
+}
+
+pub(crate) fn format_real(r: f64) -> String {
+    if r.fract() == 0.0 && r.abs() < 1e15 {
+        format!("{r:.1}")
+    } else {
+        let s = format!("{r:.15}");
+        let trimmed = s.trim_end_matches('0');
+        trimmed.trim_end_matches('.').to_string()
+    }
+}
+
+fn unquote(s: &str) -> String {
+    s.strip_prefix('\'')
+        .and_then(|s| s.strip_suffix('\''))
+        .unwrap_or(s)
+        .replace("''", "'")
+}
+
+fn decode_hex(s: &str) -> Vec<u8> {
+    let s = s.trim_start_matches(['x', 'X']).trim_matches('\'');
+    (0..s.len())
+        .step_by(2)
+        .filter_map(|i| s.get(i..i + 2).and_then(|byte| u8::from_str_radix(byte, 16).ok()))
+        .collect()
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn parse_numeric(n: &str) -> Value {
+    let looks_like_real = n.contains('.') || n.to_ascii_lowercase().contains('e');
+    if !looks_like_real {
+        if let Ok(i) = n.parse::<i64>() {
+            return Value::Integer(i);
+        }
+    }
+    n.parse::<f64>().map(Value::Real).unwrap_or(Value::Null)
+}
+
+/// The five SQLite column type affinities, and the coercion rules used to
+/// map a stored value onto a column's declared affinity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Affinity {
+    Text,
+    Numeric,
+    Integer,
+    Real,
+    Blob,
+}
+
+impl Affinity {
+    pub fn from_type_name(type_name: &str) -> Self {
+        let upper = type_name.to_ascii_uppercase();
+        if upper.contains("INT") {
+            Affinity::Integer
+        } else if upper.contains("CHAR") || upper.contains("CLOB") || upper.contains("TEXT") {
+            Affinity::Text
+        } else if upper.contains("REAL") || upper.contains("FLOA") || upper.contains("DOUB") {
+            Affinity::Real
+        } else if upper.contains("BLOB") || upper.is_empty() {
+            Affinity::Blob
+        } else {
+            Affinity::Numeric
+        }
+    }
+
+    pub fn coerce(self, value: Value) -> Value {
+        match (self, value) {
+            (Affinity::Text, Value::Integer(i)) => Value::Text(i.to_string()),
+            (Affinity::Text, Value::Real(r)) => Value::Text(format_real(r)),
+            (Affinity::Integer | Affinity::Numeric, Value::Text(s)) => numeric_from_text(&s, self),
+            (Affinity::Real, Value::Text(s)) => {
+                s.parse::<f64>().map(Value::Real).unwrap_or(Value::Text(s))
+            }
+            (Affinity::Real, Value::Integer(i)) => Value::Real(i as f64),
+            (Affinity::Integer, Value::Real(r)) => {
+                if r.fract() == 0.0 {
+                    Value::Integer(r as i64)
+                } else {
+                    Value::Real(r)
+                }
+            }
+            (_, value) => value,
+        }
+    }
+}
+
+fn numeric_from_text(s: &str, affinity: Affinity) -> Value {
+    if let Ok(i) = s.parse::<i64>() {
+        return Value::Integer(i);
+    }
+    if let Ok(r) = s.parse::<f64>() {
+        return if affinity == Affinity::Integer && r.fract() == 0.0 {
+            Value::Integer(r as i64)
+        } else {
+            Value::Real(r)
+        };
+    }
+    Value::Text(s.to_string())
+}
+
+fn storage_rank(value: &Value) -> u8 {
+    match value {
+        Value::Null => 0,
+        Value::Integer(_) | Value::Real(_) => 1,
+        Value::Text(_) => 2,
+        Value::Blob(_) => 3,
+    }
+}
+
+/// Compare two values using SQLite's storage-class ordering:
+/// `NULL < numbers < text < blob`, with numeric values compared numerically.
+pub fn compare_values(a: &Value, b: &Value) -> Ordering {
+    match (a, b) {
+        (Value::Integer(a), Value::Integer(b)) => a.cmp(b),
+        (Value::Real(a), Value::Real(b)) => a.partial_cmp(b).unwrap_or(Ordering::Equal),
+        (Value::Integer(a), Value::Real(b)) => (*a as f64).partial_cmp(b).unwrap_or(Ordering::Equal),
+        (Value::Real(a), Value::Integer(b)) => a.partial_cmp(&(*b as f64)).unwrap_or(Ordering::Equal),
+        (Value::Text(a), Value::Text(b)) => a.cmp(b),
+        (Value::Blob(a), Value::Blob(b)) => a.cmp(b),
+        _ => storage_rank(a).cmp(&storage_rank(b)),
+    }
+}
+
+/// SQLite's truthiness: NULL and zero are false, everything else is true.
+pub fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Integer(i) => *i != 0,
+        Value::Real(r) => *r != 0.0,
+        Value::Text(_) | Value::Blob(_) => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn affinity_coerces_text_to_declared_numeric_type() {
+        assert_eq!(
+            Affinity::Integer.coerce(Value::Text("3".to_string())),
+            Value::Integer(3)
+        );
+        assert_eq!(
+            Affinity::Real.coerce(Value::Text("3.5".to_string())),
+            Value::Real(3.5)
+        );
+    }
+
+    #[test]
+    fn affinity_coerces_reals_and_integers_across_each_other() {
+        assert_eq!(Affinity::Real.coerce(Value::Integer(3)), Value::Real(3.0));
+        assert_eq!(Affinity::Integer.coerce(Value::Real(3.0)), Value::Integer(3));
+        assert_eq!(
+            Affinity::Integer.coerce(Value::Real(3.5)),
+            Value::Real(3.5)
+        );
+    }
+
+    #[test]
+    fn affinity_from_type_name_maps_sqlite_type_affinity_rules() {
+        assert_eq!(Affinity::from_type_name("INT"), Affinity::Integer);
+        assert_eq!(Affinity::from_type_name("VARCHAR(10)"), Affinity::Text);
+        assert_eq!(Affinity::from_type_name("DOUBLE"), Affinity::Real);
+        assert_eq!(Affinity::from_type_name(""), Affinity::Blob);
+        assert_eq!(Affinity::from_type_name("NUMERIC"), Affinity::Numeric);
+    }
+
+    #[test]
+    fn compare_values_orders_by_storage_class_then_value() {
+        assert_eq!(compare_values(&Value::Null, &Value::Integer(0)), Ordering::Less);
+        assert_eq!(
+            compare_values(&Value::Integer(1), &Value::Text("a".to_string())),
+            Ordering::Less
+        );
+        assert_eq!(
+            compare_values(&Value::Integer(1), &Value::Real(2.0)),
+            Ordering::Less
+        );
+    }
+}
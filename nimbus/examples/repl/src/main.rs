@@ -11,6 +11,25 @@ fn main() -> Result<()> {
         let readline = rl.readline("nimbus> ");
         match readline {
             Ok(line) => {
+                if let Some(path) = line.trim().strip_prefix(".save ") {
+                    match nimbus.save(path.trim()) {
+                        Ok(()) => println!("saved to {}", path.trim()),
+                        Err(e) => println!("Error: {:?}", e),
+                    }
+                    rl.add_history_entry(line.as_str())?;
+                    continue;
+                }
+                if let Some(path) = line.trim().strip_prefix(".load ") {
+                    match Nimbus::load(path.trim()) {
+                        Ok(loaded) => {
+                            nimbus = loaded;
+                            println!("loaded from {}", path.trim());
+                        }
+                        Err(e) => println!("Error: {:?}", e),
+                    }
+                    rl.add_history_entry(line.as_str())?;
+                    continue;
+                }
                 match nimbus.eval(&line) {
                     Ok(result) => {
                         println!("{:#?}", result);
@@ -0,0 +1,68 @@
+use sqlite3_parser::ast::Literal;
+
+/// The five SQLite column type affinities, and the coercion rules used to
+/// map an inserted literal onto a column's declared affinity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Affinity {
+    Text,
+    Numeric,
+    Integer,
+    Real,
+    Blob,
+}
+
+impl Affinity {
+    pub(crate) fn from_type_name(type_name: &str) -> Self {
+        let upper = type_name.to_ascii_uppercase();
+        if upper.contains("INT") {
+            Affinity::Integer
+        } else if upper.contains("CHAR") || upper.contains("CLOB") || upper.contains("TEXT") {
+            Affinity::Text
+        } else if upper.contains("REAL") || upper.contains("FLOA") || upper.contains("DOUB") {
+            Affinity::Real
+        } else if upper.contains("BLOB") || upper.is_empty() {
+            Affinity::Blob
+        } else {
+            Affinity::Numeric
+        }
+    }
+
+    pub(crate) fn coerce(self, literal: Literal) -> Literal {
+        match (self, literal) {
+            (Affinity::Text, Literal::Numeric(n)) => Literal::String(format!("'{n}'")),
+            (Affinity::Integer | Affinity::Numeric | Affinity::Real, Literal::String(s)) => {
+                numeric_from_text(&unquote(&s), self).unwrap_or(Literal::String(s))
+            }
+            (Affinity::Integer | Affinity::Numeric, Literal::Numeric(n)) => {
+                numeric_from_text(&n, self).unwrap_or(Literal::Numeric(n))
+            }
+            (Affinity::Real, Literal::Numeric(n)) => match n.parse::<f64>() {
+                Ok(r) => Literal::Numeric(r.to_string()),
+                Err(_) => Literal::Numeric(n),
+            },
+            (_, literal) => literal,
+        }
+    }
+}
+
+fn unquote(s: &str) -> String {
+    s.strip_prefix('\'')
+        .and_then(|s| s.strip_suffix('\''))
+        .unwrap_or(s)
+        .replace("''", "'")
+}
+
+fn numeric_from_text(s: &str, affinity: Affinity) -> Option<Literal> {
+    let trimmed = s.trim();
+    if let Ok(i) = trimmed.parse::<i64>() {
+        return Some(Literal::Numeric(i.to_string()));
+    }
+    if let Ok(r) = trimmed.parse::<f64>() {
+        return Some(if affinity == Affinity::Integer && r.fract() == 0.0 {
+            Literal::Numeric((r as i64).to_string())
+        } else {
+            Literal::Numeric(r.to_string())
+        });
+    }
+    None
+}
@@ -0,0 +1,168 @@
+use crate::params::Params;
+use anyhow::{bail, Context};
+use sqlite3_parser::ast::{Expr, Literal, Operator, UnaryOperator};
+use std::cmp::Ordering;
+
+/// Recursively evaluate `expr` against a single row, resolving
+/// `Expr::Id`/`Expr::Qualified` column references by name via `columns` and
+/// `Expr::Variable` placeholders via `params`. Shared by SELECT's WHERE
+/// clause and UPDATE/DELETE, so comparison semantics stay in one place.
+pub(crate) fn eval(
+    expr: &Expr,
+    row: &[Literal],
+    columns: &[String],
+    params: &Params,
+) -> anyhow::Result<Literal> {
+    match expr {
+        Expr::Literal(literal) => Ok(literal.clone()),
+        Expr::Id(id) => column(row, columns, &id.0),
+        Expr::Qualified(_, id) => column(row, columns, &id.0),
+        Expr::Variable(raw) => params.resolve(raw),
+        Expr::Parenthesized(exprs) if exprs.len() == 1 => eval(&exprs[0], row, columns, params),
+        Expr::Unary(op, inner) => eval_unary(*op, eval(inner, row, columns, params)?),
+        Expr::Binary(lhs, op, rhs) => eval_binary(
+            *op,
+            eval(lhs, row, columns, params)?,
+            eval(rhs, row, columns, params)?,
+        ),
+        _ => bail!("unsupported expression: {expr:?}"),
+    }
+}
+
+fn column(row: &[Literal], columns: &[String], name: &str) -> anyhow::Result<Literal> {
+    let idx = columns
+        .iter()
+        .position(|c| c.eq_ignore_ascii_case(name))
+        .with_context(|| format!("no such column: {name}"))?;
+    Ok(row[idx].clone())
+}
+
+fn eval_unary(op: UnaryOperator, value: Literal) -> anyhow::Result<Literal> {
+    match op {
+        UnaryOperator::Not => Ok(match value {
+            Literal::Null => Literal::Null,
+            other => bool_literal(!is_truthy(&other)),
+        }),
+        _ => bail!("unsupported unary operator: {op:?}"),
+    }
+}
+
+fn eval_binary(op: Operator, lhs: Literal, rhs: Literal) -> anyhow::Result<Literal> {
+    match op {
+        Operator::And => Ok(match (truth(&lhs), truth(&rhs)) {
+            (Some(false), _) | (_, Some(false)) => bool_literal(false),
+            (Some(true), Some(true)) => bool_literal(true),
+            _ => Literal::Null,
+        }),
+        Operator::Or => Ok(match (truth(&lhs), truth(&rhs)) {
+            (Some(true), _) | (_, Some(true)) => bool_literal(true),
+            (Some(false), Some(false)) => bool_literal(false),
+            _ => Literal::Null,
+        }),
+        Operator::Equals
+        | Operator::NotEquals
+        | Operator::Less
+        | Operator::LessEquals
+        | Operator::Greater
+        | Operator::GreaterEquals => {
+            if matches!(lhs, Literal::Null) || matches!(rhs, Literal::Null) {
+                return Ok(Literal::Null);
+            }
+            let ordering = compare_literals(&lhs, &rhs);
+            let result = match op {
+                Operator::Equals => ordering.is_eq(),
+                Operator::NotEquals => ordering.is_ne(),
+                Operator::Less => ordering.is_lt(),
+                Operator::LessEquals => ordering.is_le(),
+                Operator::Greater => ordering.is_gt(),
+                Operator::GreaterEquals => ordering.is_ge(),
+                _ => unreachable!(),
+            };
+            Ok(bool_literal(result))
+        }
+        _ => bail!("unsupported binary operator: {op:?}"),
+    }
+}
+
+fn bool_literal(value: bool) -> Literal {
+    Literal::Numeric(if value { "1" } else { "0" }.to_string())
+}
+
+/// `Some(true)`/`Some(false)` for a definite value, `None` for `NULL` —
+/// used to implement AND/OR's three-valued truth table.
+fn truth(literal: &Literal) -> Option<bool> {
+    match literal {
+        Literal::Null => None,
+        other => Some(is_truthy(other)),
+    }
+}
+
+/// SQLite's truthiness for a predicate result: NULL and zero are false,
+/// any other numeric value is true. Non-numeric text and blobs coerce to
+/// zero, matching SQLite's numeric affinity conversion for booleans.
+pub(crate) fn is_truthy(literal: &Literal) -> bool {
+    match literal {
+        Literal::Null => false,
+        other => as_f64(other).unwrap_or(0.0) != 0.0,
+    }
+}
+
+fn as_f64(literal: &Literal) -> Option<f64> {
+    match literal {
+        Literal::Numeric(n) => n.parse().ok(),
+        Literal::String(s) => unquote(s).trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn unquote(s: &str) -> String {
+    s.strip_prefix('\'')
+        .and_then(|s| s.strip_suffix('\''))
+        .unwrap_or(s)
+        .replace("''", "'")
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+enum StorageClass {
+    Null,
+    Number,
+    Text,
+    Blob,
+}
+
+fn storage_class(literal: &Literal) -> StorageClass {
+    match literal {
+        Literal::Null => StorageClass::Null,
+        Literal::Numeric(_) => StorageClass::Number,
+        Literal::Blob(_) => StorageClass::Blob,
+        _ => StorageClass::Text,
+    }
+}
+
+/// Compare two literals using SQLite's storage-class ordering:
+/// `NULL < numbers < text < blob`, with numbers compared numerically and
+/// text compared after stripping quotes.
+pub(crate) fn compare_literals(a: &Literal, b: &Literal) -> Ordering {
+    let (class_a, class_b) = (storage_class(a), storage_class(b));
+    if class_a != class_b {
+        return class_a.cmp(&class_b);
+    }
+    match class_a {
+        StorageClass::Null => Ordering::Equal,
+        StorageClass::Number => as_f64(a)
+            .unwrap_or(0.0)
+            .partial_cmp(&as_f64(b).unwrap_or(0.0))
+            .unwrap_or(Ordering::Equal),
+        StorageClass::Text => text_of(a).cmp(&text_of(b)),
+        StorageClass::Blob => text_of(a).cmp(&text_of(b)),
+    }
+}
+
+fn text_of(literal: &Literal) -> String {
+    match literal {
+        Literal::String(s) => unquote(s),
+        Literal::Blob(b) => b.clone(),
+        Literal::Keyword(k) => k.clone(),
+        other => format!("{other:?}"),
+    }
+}
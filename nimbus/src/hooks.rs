@@ -0,0 +1,78 @@
+/// The kind of row-level mutation reported to an update hook, mirroring
+/// `sqlite3_update_hook`'s `SQLITE_INSERT`/`SQLITE_UPDATE`/`SQLITE_DELETE`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Insert,
+    Update,
+    Delete,
+}
+
+impl From<crate::ChangeOp> for Action {
+    fn from(op: crate::ChangeOp) -> Self {
+        match op {
+            crate::ChangeOp::Insert => Action::Insert,
+            crate::ChangeOp::Update => Action::Update,
+            crate::ChangeOp::Delete => Action::Delete,
+        }
+    }
+}
+
+/// The callback slots an embedder can attach via `Nimbus::set_trace` and
+/// friends. Each slot holds at most one callback, matching rusqlite's hook
+/// API (a later `set_*` call replaces, rather than chains, the previous
+/// callback).
+#[derive(Default)]
+pub(crate) struct Hooks {
+    trace: Option<Box<dyn FnMut(&str)>>,
+    update: Option<Box<dyn FnMut(Action, &str, usize)>>,
+    commit: Option<Box<dyn FnMut()>>,
+    rollback: Option<Box<dyn FnMut()>>,
+}
+
+impl std::fmt::Debug for Hooks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Hooks").finish_non_exhaustive()
+    }
+}
+
+impl Hooks {
+    pub(crate) fn set_trace(&mut self, f: impl FnMut(&str) + 'static) {
+        self.trace = Some(Box::new(f));
+    }
+
+    pub(crate) fn set_update_hook(&mut self, f: impl FnMut(Action, &str, usize) + 'static) {
+        self.update = Some(Box::new(f));
+    }
+
+    pub(crate) fn set_commit_hook(&mut self, f: impl FnMut() + 'static) {
+        self.commit = Some(Box::new(f));
+    }
+
+    pub(crate) fn set_rollback_hook(&mut self, f: impl FnMut() + 'static) {
+        self.rollback = Some(Box::new(f));
+    }
+
+    pub(crate) fn trace(&mut self, sql: &str) {
+        if let Some(trace) = self.trace.as_mut() {
+            trace(sql);
+        }
+    }
+
+    pub(crate) fn fire_update(&mut self, action: Action, table_name: &str, row_index: usize) {
+        if let Some(update) = self.update.as_mut() {
+            update(action, table_name, row_index);
+        }
+    }
+
+    pub(crate) fn fire_commit(&mut self) {
+        if let Some(commit) = self.commit.as_mut() {
+            commit();
+        }
+    }
+
+    pub(crate) fn fire_rollback(&mut self) {
+        if let Some(rollback) = self.rollback.as_mut() {
+            rollback();
+        }
+    }
+}
@@ -1,11 +1,24 @@
-use anyhow::{anyhow, bail};
+use anyhow::{anyhow, bail, Context};
 use fallible_iterator::FallibleIterator;
 use indexmap::Entries;
-use log::info;
 use sqlite3_parser::ast::{
-    Cmd, Expr, FromClause, InsertBody, JoinOperator, JoinType, JoinedSelectTable, Literal, Name,
-    OneSelect, QualifiedName, ResultColumn, Select, SelectBody, SelectTable, Stmt,
+    Cmd, CreateTableBody, Expr, FromClause, InsertBody, JoinOperator, JoinType, JoinedSelectTable,
+    Literal, Name, OneSelect, QualifiedName, ResultColumn, Select, SelectBody, SelectTable, Stmt,
 };
+use std::collections::HashMap;
+use std::path::Path;
+
+mod affinity;
+mod eval;
+mod hooks;
+mod params;
+mod session;
+
+use affinity::Affinity;
+use eval::{eval, is_truthy};
+pub use hooks::Action;
+pub use params::Params;
+pub use session::{Change, ChangeOp, Changeset, Session};
 
 #[cfg(test)]
 use ctor::ctor;
@@ -54,7 +67,6 @@ impl NimbusTable {
         }
     }
 
-    #[allow(dead_code)]
     fn name(&self) -> &String {
         if let Stmt::CreateTable { ref tbl_name, .. } = &self.create_stmt {
             match tbl_name {
@@ -66,11 +78,72 @@ impl NimbusTable {
             panic!("developer error.")
         }
     }
+
+    /// Column names in declaration order, used to resolve `Expr::Id`/
+    /// `Expr::Qualified` references when evaluating WHERE predicates.
+    fn column_names(&self) -> Vec<String> {
+        if let Stmt::CreateTable { ref body, .. } = &self.create_stmt {
+            match body {
+                CreateTableBody::ColumnsAndConstraints { columns, .. } => {
+                    columns.iter().map(|c| c.col_name.0.clone()).collect()
+                }
+                CreateTableBody::AsSelect(_) => vec![],
+            }
+        } else {
+            panic!("developer error.")
+        }
+    }
+
+    /// Column affinities in declaration order, derived from each column's
+    /// declared type name per SQLite's affinity rules.
+    fn column_affinities(&self) -> Vec<Affinity> {
+        if let Stmt::CreateTable { ref body, .. } = &self.create_stmt {
+            match body {
+                CreateTableBody::ColumnsAndConstraints { columns, .. } => columns
+                    .iter()
+                    .map(|c| {
+                        c.col_type
+                            .as_ref()
+                            .map(|t| Affinity::from_type_name(&t.name))
+                            .unwrap_or(Affinity::Blob)
+                    })
+                    .collect(),
+                CreateTableBody::AsSelect(_) => vec![],
+            }
+        } else {
+            panic!("developer error.")
+        }
+    }
+
+    /// Re-render this table's `CREATE TABLE` as SQL text, for persistence
+    /// (`Nimbus::save`/`Nimbus::load`). Only column name/type-name pairs
+    /// round-trip; other constraints are dropped, the same simplification
+    /// `synthesize_create_table` already makes for CSV virtual tables.
+    fn to_create_sql(&self) -> String {
+        if let Stmt::CreateTable { ref body, .. } = &self.create_stmt {
+            let column_defs = match body {
+                CreateTableBody::ColumnsAndConstraints { columns, .. } => columns
+                    .iter()
+                    .map(|c| match &c.col_type {
+                        Some(t) => format!("{} {}", c.col_name.0, t.name),
+                        None => c.col_name.0.clone(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                CreateTableBody::AsSelect(_) => String::new(),
+            };
+            format!("CREATE TABLE {}({column_defs})", self.name())
+        } else {
+            panic!("developer error.")
+        }
+    }
 }
 
 #[derive(Default, Debug)]
 struct NimbusData {
     tables: indexmap::IndexSet<NimbusTable>,
+    session: Option<Session>,
+    hooks: hooks::Hooks,
 }
 
 impl NimbusData {
@@ -87,7 +160,89 @@ impl NimbusData {
         }
     }
 
-    fn execute(&mut self, stmt: Stmt) -> anyhow::Result<NimbusExecuteResult> {
+    /// Like `get_table`, but looked up by plain table name rather than a
+    /// parsed `QualifiedName` — used when replaying a [`Changeset`], which
+    /// only carries the table name as a string.
+    fn get_table_by_name(&mut self, table_name: &str) -> Option<&mut NimbusTable> {
+        if let Some(bucket) = self
+            .tables
+            .as_entries_mut()
+            .iter_mut()
+            .find(|bucket| bucket.key.name().eq_ignore_ascii_case(table_name))
+        {
+            Some(&mut bucket.key)
+        } else {
+            None
+        }
+    }
+
+    /// Append a mutation to the active session, if one is recording.
+    fn record_change(
+        &mut self,
+        table_name: &str,
+        op: ChangeOp,
+        old_row: Option<Vec<Literal>>,
+        new_row: Option<Vec<Literal>>,
+    ) {
+        if let Some(session) = self.session.as_mut() {
+            session.record(Change {
+                table_name: table_name.to_string(),
+                op,
+                old_row,
+                new_row,
+            });
+        }
+    }
+
+    /// Replay a single recorded mutation directly against table storage,
+    /// bypassing SQL parsing entirely. Used to apply a [`Changeset`] (or its
+    /// `invert()`) recorded by a session, whether this `NimbusData`'s own or
+    /// another's.
+    fn apply_change(&mut self, change: &Change) -> anyhow::Result<()> {
+        let nimbus_table = self
+            .get_table_by_name(&change.table_name)
+            .ok_or_else(|| anyhow!("no such table: {}", change.table_name))?;
+        match change.op {
+            ChangeOp::Insert => {
+                let new_row = change
+                    .new_row
+                    .clone()
+                    .ok_or_else(|| anyhow!("insert change missing new_row"))?;
+                nimbus_table.data.push(new_row);
+            }
+            ChangeOp::Delete => {
+                let old_row = change
+                    .old_row
+                    .as_ref()
+                    .ok_or_else(|| anyhow!("delete change missing old_row"))?;
+                let idx = nimbus_table
+                    .data
+                    .iter()
+                    .position(|row| row == old_row)
+                    .ok_or_else(|| anyhow!("no matching row to delete in {}", change.table_name))?;
+                nimbus_table.data.remove(idx);
+            }
+            ChangeOp::Update => {
+                let old_row = change
+                    .old_row
+                    .as_ref()
+                    .ok_or_else(|| anyhow!("update change missing old_row"))?;
+                let new_row = change
+                    .new_row
+                    .clone()
+                    .ok_or_else(|| anyhow!("update change missing new_row"))?;
+                let idx = nimbus_table
+                    .data
+                    .iter()
+                    .position(|row| row == old_row)
+                    .ok_or_else(|| anyhow!("no matching row to update in {}", change.table_name))?;
+                nimbus_table.data[idx] = new_row;
+            }
+        }
+        Ok(())
+    }
+
+    fn execute(&mut self, stmt: Stmt, params: &Params) -> anyhow::Result<NimbusExecuteResult> {
         match stmt {
             Stmt::CreateTable { .. } => Ok(NimbusExecuteResult::CreateTableResult(
                 self.tables.insert(NimbusTable::from_create_stmt(stmt)),
@@ -119,11 +274,10 @@ impl NimbusData {
                                         window_clause,
                                     } => {
                                         if distinctness.is_some()
-                                            | where_clause.is_some()
                                             | group_by.is_some()
                                             | window_clause.is_some()
                                         {
-                                            bail!("one-select-(distinctness|where|group_by|window_clause) not supported");
+                                            bail!("one-select-(distinctness|group_by|window_clause) not supported");
                                         }
 
                                         let tbl_name = from
@@ -200,9 +354,26 @@ impl NimbusData {
                                                     }
                                                 };
 
-                                                Ok(NimbusExecuteResult::SelectResult(
-                                                    nimbus_table.data.clone(),
-                                                ))
+                                                let column_names = nimbus_table.column_names();
+                                                let rows = match where_clause {
+                                                    None => nimbus_table.data.clone(),
+                                                    Some(expr) => {
+                                                        let mut rows = vec![];
+                                                        for row in &nimbus_table.data {
+                                                            if is_truthy(&eval(
+                                                                expr,
+                                                                row,
+                                                                &column_names,
+                                                                params,
+                                                            )?) {
+                                                                rows.push(row.clone());
+                                                            }
+                                                        }
+                                                        rows
+                                                    }
+                                                };
+
+                                                Ok(NimbusExecuteResult::SelectResult(rows))
                                             }
                                         }
                                     }
@@ -215,8 +386,105 @@ impl NimbusData {
                     }
                 }
             }
-            Stmt::Update { .. } => {
-                todo!()
+            Stmt::Update {
+                with,
+                or_conflict,
+                tbl_name,
+                indexed,
+                sets,
+                from,
+                where_clause,
+                returning,
+                order_by,
+                limit,
+            } => {
+                if with.is_some()
+                    | or_conflict.is_some()
+                    | indexed.is_some()
+                    | from.is_some()
+                    | returning.is_some()
+                    | order_by.is_some()
+                    | limit.is_some()
+                {
+                    bail!("update-(with|or_conflict|indexed|from|returning|order_by|limit) not supported");
+                }
+                let table_name = qualified_name_to_string(&tbl_name);
+                let Some(nimbus_table) = self.get_table(&tbl_name) else {
+                    bail!("no such table: {}", tbl_name);
+                };
+                let column_names = nimbus_table.column_names();
+                let mut changed = 0usize;
+                let mut updates = vec![];
+                for (idx, row) in nimbus_table.data.iter_mut().enumerate() {
+                    let matches = match &where_clause {
+                        None => true,
+                        Some(expr) => is_truthy(&eval(expr, row, &column_names, params)?),
+                    };
+                    if !matches {
+                        continue;
+                    }
+                    let snapshot = row.clone();
+                    for set in &sets {
+                        let value = eval(&set.expr, &snapshot, &column_names, params)?;
+                        for name in &set.col_names {
+                            let idx = column_names
+                                .iter()
+                                .position(|c| c.eq_ignore_ascii_case(&name.0))
+                                .ok_or_else(|| anyhow!("no such column: {}", name.0))?;
+                            row[idx] = value.clone();
+                        }
+                    }
+                    updates.push((idx, snapshot, row.clone()));
+                    changed += 1;
+                }
+                for (idx, old_row, new_row) in updates {
+                    self.hooks.fire_update(Action::Update, &table_name, idx);
+                    self.record_change(&table_name, ChangeOp::Update, Some(old_row), Some(new_row));
+                }
+                Ok(NimbusExecuteResult::ChangeResult(changed))
+            }
+            Stmt::Delete {
+                with,
+                tbl_name,
+                indexed,
+                where_clause,
+                returning,
+                order_by,
+                limit,
+            } => {
+                if with.is_some()
+                    | indexed.is_some()
+                    | returning.is_some()
+                    | order_by.is_some()
+                    | limit.is_some()
+                {
+                    bail!("delete-(with|indexed|returning|order_by|limit) not supported");
+                }
+                let table_name = qualified_name_to_string(&tbl_name);
+                let Some(nimbus_table) = self.get_table(&tbl_name) else {
+                    bail!("no such table: {}", tbl_name);
+                };
+                let column_names = nimbus_table.column_names();
+                let mut deleted = vec![];
+                let mut kept = vec![];
+                for (idx, row) in nimbus_table.data.drain(..).collect::<Vec<_>>().into_iter().enumerate() {
+                    let matches = match &where_clause {
+                        None => true,
+                        Some(expr) => is_truthy(&eval(expr, &row, &column_names, params)?),
+                    };
+                    if matches {
+                        deleted.push((idx, row));
+                    } else {
+                        kept.push(row);
+                    }
+                }
+                nimbus_table.data = kept;
+                let changed = deleted.len();
+                for (idx, row) in deleted {
+                    self.hooks.fire_update(Action::Delete, &table_name, idx);
+                    self.record_change(&table_name, ChangeOp::Delete, Some(row), None);
+                }
+                Ok(NimbusExecuteResult::ChangeResult(changed))
             }
             Stmt::Insert {
                 with,
@@ -230,7 +498,9 @@ impl NimbusData {
                 {
                     bail!("insert-(with|or_conflict|columns|returning) not supported");
                 }
-                if let Some(nimbus_table) = self.get_table(&tbl_name) {
+                let table_name = qualified_name_to_string(&tbl_name);
+                let mut inserted = vec![];
+                let result = if let Some(nimbus_table) = self.get_table(&tbl_name) {
                     if columns.is_some() {
                         bail!("stmt-insert-columns not supported");
                     }
@@ -259,17 +529,28 @@ impl NimbusData {
                                                     bail!("insert-body-select-one-select not supported");
                                                 }
                                                 OneSelect::Values(values) => {
+                                                    let affinities = nimbus_table.column_affinities();
                                                     for row in values {
                                                         let mut insert_row = vec![];
-                                                        for expr in row {
+                                                        for (expr, affinity) in
+                                                            row.iter().zip(affinities.iter())
+                                                        {
                                                             match expr {
                                                                 Expr::Literal(literal) => {
-                                                                    insert_row.push(literal.clone());
+                                                                    insert_row.push(
+                                                                        affinity.coerce(literal.clone()),
+                                                                    );
+                                                                }
+                                                                Expr::Variable(raw) => {
+                                                                    let literal = params.resolve(raw)?;
+                                                                    insert_row.push(affinity.coerce(literal));
                                                                 }
-                                                                _ => bail!("only literal expressions supported")
+                                                                _ => bail!("only literal or bound-parameter expressions supported")
                                                             }
                                                         }
-                                                        nimbus_table.data.push(insert_row);
+                                                        let idx = nimbus_table.data.len();
+                                                        nimbus_table.data.push(insert_row.clone());
+                                                        inserted.push((idx, insert_row));
                                                     }
                                                     Ok(NimbusExecuteResult::InsertResult)
                                                 }
@@ -284,8 +565,74 @@ impl NimbusData {
                         }
                     }
                 } else {
-                    bail!("no such table: foo");
+                    bail!("no such table: {}", tbl_name);
+                };
+                for (idx, row) in inserted {
+                    self.hooks.fire_update(Action::Insert, &table_name, idx);
+                    self.record_change(&table_name, ChangeOp::Insert, None, Some(row));
+                }
+                result
+            }
+            Stmt::CreateVirtualTable {
+                if_not_exists,
+                tbl_name,
+                module_name,
+                args,
+            } => {
+                if module_name.0 != "csv" {
+                    bail!("unsupported virtual table module: {}", module_name.0);
+                }
+                if if_not_exists && self.get_table(&tbl_name).is_some() {
+                    return Ok(NimbusExecuteResult::CreateTableResult(false));
+                }
+                let options = parse_csv_args(&args.unwrap_or_default())?;
+                let filename = options
+                    .get("filename")
+                    .ok_or_else(|| anyhow!("csv virtual table requires a filename argument"))?;
+                let header = options
+                    .get("header")
+                    .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+                    .unwrap_or(true);
+
+                let contents = std::fs::read_to_string(filename)
+                    .with_context(|| format!("reading csv file {filename}"))?;
+                let mut lines = contents.lines();
+                let (column_names, first_data_line) = if header {
+                    let header_line = lines
+                        .next()
+                        .ok_or_else(|| anyhow!("csv file {filename} is empty"))?;
+                    (
+                        header_line.split(',').map(str::trim).map(str::to_string).collect(),
+                        None,
+                    )
+                } else {
+                    let first = lines.next();
+                    let count = first.map(|l| l.split(',').count()).unwrap_or(0);
+                    ((0..count).map(|i| format!("c{i}")).collect(), first)
+                };
+
+                let create_stmt = synthesize_create_table(&tbl_name, &column_names)?;
+                let mut nimbus_table = NimbusTable::from_create_stmt(create_stmt);
+                for line in first_data_line.into_iter().chain(lines) {
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    nimbus_table
+                        .data
+                        .push(line.split(',').map(|field| infer_csv_literal(field.trim())).collect());
                 }
+                Ok(NimbusExecuteResult::CreateTableResult(
+                    self.tables.insert(nimbus_table),
+                ))
+            }
+            Stmt::Begin(..) => Ok(NimbusExecuteResult::NoneResult),
+            Stmt::Commit(..) => {
+                self.hooks.fire_commit();
+                Ok(NimbusExecuteResult::NoneResult)
+            }
+            Stmt::Rollback { .. } => {
+                self.hooks.fire_rollback();
+                Ok(NimbusExecuteResult::NoneResult)
             }
             _ => {
                 bail!("unsupported statement");
@@ -294,6 +641,70 @@ impl NimbusData {
     }
 }
 
+fn qualified_name_to_string(q: &QualifiedName) -> String {
+    let QualifiedName { name: Name(name), .. } = q;
+    name.clone()
+}
+
+/// Parse `CREATE VIRTUAL TABLE ... USING csv(key='value', ...)` arguments
+/// into a key/value map, stripping the quotes SQLite's virtual-table module
+/// syntax wraps string values in.
+fn parse_csv_args(args: &[String]) -> anyhow::Result<HashMap<String, String>> {
+    let mut options = HashMap::new();
+    for arg in args {
+        let (key, value) = arg
+            .split_once('=')
+            .ok_or_else(|| anyhow!("malformed csv virtual table argument: {arg}"))?;
+        let value = value.trim().trim_matches(['\'', '"']).to_string();
+        options.insert(key.trim().to_string(), value);
+    }
+    Ok(options)
+}
+
+/// Infer a CSV field's storage class the way SQLite's csvtab module does:
+/// numeric-looking text becomes a numeric literal, everything else stays
+/// text, and an empty field is NULL.
+fn infer_csv_literal(field: &str) -> Literal {
+    if field.is_empty() {
+        Literal::Null
+    } else if field.parse::<i64>().is_ok() || field.parse::<f64>().is_ok() {
+        Literal::Numeric(field.to_string())
+    } else {
+        Literal::String(format!("'{}'", field.replace('\'', "''")))
+    }
+}
+
+/// Build a genuine `Stmt::CreateTable` AST node for a CSV-backed table by
+/// rendering and re-parsing `CREATE TABLE name(col, ...)`, so the rest of
+/// `NimbusTable` (which assumes a real `CreateTable` statement) works
+/// unchanged for virtual tables too.
+fn synthesize_create_table(tbl_name: &QualifiedName, column_names: &[String]) -> anyhow::Result<Stmt> {
+    let name = qualified_name_to_string(tbl_name);
+    let create_sql = format!("CREATE TABLE {name}({})", column_names.join(", "));
+    let mut parser = sqlite3_parser::lexer::sql::Parser::new(create_sql.as_bytes());
+    match parser.next()? {
+        Some(Cmd::Stmt(stmt @ Stmt::CreateTable { .. })) => Ok(stmt),
+        _ => bail!("failed to synthesize CREATE TABLE for csv table {name}"),
+    }
+}
+
+/// Render a stored `Literal` back to SQL text, for persistence
+/// (`Nimbus::save`). `Literal::String`/`Literal::Numeric`/etc. already hold
+/// their SQL-ready text (e.g. `Literal::String` includes the surrounding
+/// quotes), so this is mostly a matter of naming the keyword variants.
+fn literal_to_sql(literal: &Literal) -> String {
+    match literal {
+        Literal::Null => "NULL".to_string(),
+        Literal::Numeric(n) => n.clone(),
+        Literal::String(s) => s.clone(),
+        Literal::Blob(b) => format!("x'{b}'"),
+        Literal::Keyword(k) => k.clone(),
+        Literal::CurrentDate => "CURRENT_DATE".to_string(),
+        Literal::CurrentTime => "CURRENT_TIME".to_string(),
+        Literal::CurrentTimestamp => "CURRENT_TIMESTAMP".to_string(),
+    }
+}
+
 #[derive(Debug)]
 pub enum NimbusExecuteResult {
     NoneResult,
@@ -302,6 +713,8 @@ pub enum NimbusExecuteResult {
     InsertResult,
     #[allow(dead_code)]
     SelectResult(Vec<Vec<Literal>>),
+    #[allow(dead_code)]
+    ChangeResult(usize),
 }
 
 #[allow(dead_code)]
@@ -318,36 +731,128 @@ impl Nimbus {
     }
 
     pub fn eval(&mut self, input: &str) -> anyhow::Result<NimbusExecuteResult> {
+        self.eval_with_params(input, &Params::none())
+    }
+
+    /// Start recording every row-level mutation made from here on. Replaces
+    /// any session already in progress, discarding its changes.
+    pub fn start_session(&mut self) {
+        self.data.session = Some(Session::default());
+    }
+
+    /// The in-progress session, if `start_session` has been called.
+    pub fn session(&self) -> Option<&Session> {
+        self.data.session.as_ref()
+    }
+
+    /// Replay a [`Changeset`] (e.g. `self.session().unwrap().changeset()`,
+    /// or its `invert()`) directly against this database's tables.
+    pub fn apply_changeset(&mut self, changeset: &Changeset) -> anyhow::Result<()> {
+        for change in &changeset.changes {
+            self.data.apply_change(change)?;
+        }
+        Ok(())
+    }
+
+    /// Snapshot every table to `path` as plain SQL text: each table's
+    /// `CREATE TABLE` statement followed by one semicolon-terminated
+    /// `INSERT INTO ... VALUES` statement per row. Statements are
+    /// semicolon-delimited (not newline-delimited) so a TEXT value
+    /// containing an embedded newline still round-trips through `load`.
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let mut out = String::new();
+        for table in &self.data.tables {
+            out.push_str(&table.to_create_sql());
+            out.push_str(";\n");
+            let table_name = table.name();
+            for row in &table.data {
+                let values = row.iter().map(literal_to_sql).collect::<Vec<_>>().join(", ");
+                out.push_str(&format!("INSERT INTO {table_name} VALUES ({values});\n"));
+            }
+        }
+        std::fs::write(path, out)?;
+        Ok(())
+    }
+
+    /// Reconstruct a `Nimbus` from a file written by `save`, by re-parsing
+    /// the snapshot's semicolon-delimited statements through a single
+    /// `sqlite3_parser` pass (rather than splitting on lines, which would
+    /// break on a TEXT value containing an embedded newline) and replaying
+    /// each one.
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let contents = std::fs::read_to_string(path.as_ref())
+            .with_context(|| format!("reading nimbus snapshot {}", path.as_ref().display()))?;
+        let mut nimbus = Self::new();
+        let bytes: Vec<u8> = contents.into_bytes();
+        let mut parser = sqlite3_parser::lexer::sql::Parser::new(bytes.as_ref());
+        while let Some(cmd) = parser.next()? {
+            match cmd {
+                Cmd::Explain(_) => bail!("cmd-explain not supported"),
+                Cmd::ExplainQueryPlan(_) => bail!("cmd-explain-query-plan not supported"),
+                Cmd::Stmt(stmt) => {
+                    nimbus.data.execute(stmt, &Params::none())?;
+                }
+            }
+        }
+        Ok(nimbus)
+    }
+
+    /// Like `eval`, but binds `?`, `?NNN`, and `:name`/`@name`/`$name`
+    /// placeholders in the parsed statement to `params` before evaluation.
+    pub fn eval_with_params(
+        &mut self,
+        input: &str,
+        params: &Params,
+    ) -> anyhow::Result<NimbusExecuteResult> {
+        self.data.hooks.trace(input);
         let input: Vec<u8> = input.into();
         let mut parser = sqlite3_parser::lexer::sql::Parser::new(input.as_ref());
 
         match parser.next()? {
             None => Ok(NimbusExecuteResult::NoneResult),
-            Some(cmd) => {
-                let result = match cmd {
-                    Cmd::Explain(_) => {
-                        bail!("cmd-explain not supported");
-                    }
-                    Cmd::ExplainQueryPlan(_) => {
-                        bail!("cmd-explain-query-plan not supported")
-                    }
-                    Cmd::Stmt(ref stmt) => Ok(self.data.execute(stmt.clone())?),
-                };
-
-                if result.is_ok() {
-                    // info!("{}", cmd)
+            Some(cmd) => match cmd {
+                Cmd::Explain(_) => {
+                    bail!("cmd-explain not supported");
                 }
-                result
-            }
+                Cmd::ExplainQueryPlan(_) => {
+                    bail!("cmd-explain-query-plan not supported")
+                }
+                Cmd::Stmt(ref stmt) => self.data.execute(stmt.clone(), params),
+            },
         }
     }
+
+    /// Register a trace callback, invoked with each statement's raw SQL
+    /// text before it's parsed and executed.
+    pub fn set_trace(&mut self, f: impl FnMut(&str) + 'static) {
+        self.data.hooks.set_trace(f);
+    }
+
+    /// Register an update hook, invoked after each row INSERT/UPDATE/DELETE
+    /// with the kind of change, the table name, and the row's index within
+    /// the table's storage (nimbus has no rowid concept, so this is a
+    /// storage-order index rather than a stable identifier).
+    pub fn set_update_hook(&mut self, f: impl FnMut(Action, &str, usize) + 'static) {
+        self.data.hooks.set_update_hook(f);
+    }
+
+    /// Register a commit hook, invoked when a `COMMIT` statement executes.
+    pub fn set_commit_hook(&mut self, f: impl FnMut() + 'static) {
+        self.data.hooks.set_commit_hook(f);
+    }
+
+    /// Register a rollback hook, invoked when a `ROLLBACK` statement executes.
+    pub fn set_rollback_hook(&mut self, f: impl FnMut() + 'static) {
+        self.data.hooks.set_rollback_hook(f);
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::Nimbus;
+    use crate::{Action, Nimbus, NimbusExecuteResult, Params};
     use insta::assert_debug_snapshot;
     use parse_sqlite_test::SqliteTestStatement;
+    use sqlite3_parser::ast::Literal;
     use std::fmt::format;
 
     #[test]
@@ -368,6 +873,233 @@ mod tests {
             .collect::<Vec<_>>());
     }
 
+    #[test]
+    fn select_filters_rows_by_where_clause() {
+        let mut nimbus = Nimbus::new();
+        nimbus.eval("create table tbl1(one text, two int)").unwrap();
+        nimbus.eval("insert into tbl1 values ('abc', 2)").unwrap();
+        nimbus.eval("insert into tbl1 values ('def', 3)").unwrap();
+        nimbus.eval("insert into tbl1 values ('ghi', 4)").unwrap();
+
+        let select = nimbus.eval("select * from tbl1 where two > 2").unwrap();
+        let NimbusExecuteResult::SelectResult(rows) = select else {
+            panic!("expected a SelectResult");
+        };
+        assert_eq!(
+            rows,
+            vec![
+                vec![
+                    Literal::String("'def'".to_string()),
+                    Literal::Numeric("3".to_string()),
+                ],
+                vec![
+                    Literal::String("'ghi'".to_string()),
+                    Literal::Numeric("4".to_string()),
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn insert_coerces_literals_to_column_affinity() {
+        let mut nimbus = Nimbus::new();
+        nimbus.eval("create table tbl1(one text, two int)").unwrap();
+        nimbus.eval("insert into tbl1 values ('2', 3.0)").unwrap();
+        let select = nimbus.eval("select * from tbl1").unwrap();
+        let NimbusExecuteResult::SelectResult(rows) = select else {
+            panic!("expected a SelectResult");
+        };
+        assert_eq!(
+            rows,
+            vec![vec![
+                Literal::String("'2'".to_string()),
+                Literal::Numeric("3".to_string()),
+            ]]
+        );
+    }
+
+    #[test]
+    fn insert_coerces_numeric_literal_to_real_column_affinity() {
+        let mut nimbus = Nimbus::new();
+        nimbus.eval("create table tbl1(r real)").unwrap();
+        nimbus.eval("insert into tbl1 values (3e2)").unwrap();
+        let select = nimbus.eval("select * from tbl1").unwrap();
+        let NimbusExecuteResult::SelectResult(rows) = select else {
+            panic!("expected a SelectResult");
+        };
+        assert_eq!(rows, vec![vec![Literal::Numeric("300".to_string())]]);
+    }
+
+    #[test]
+    fn update_and_delete_mutate_matching_rows() {
+        let mut nimbus = Nimbus::new();
+        nimbus.eval("create table tbl1(one text, two int)").unwrap();
+        nimbus.eval("insert into tbl1 values ('abc', 2)").unwrap();
+        nimbus.eval("insert into tbl1 values ('def', 3)").unwrap();
+        nimbus.eval("insert into tbl1 values ('ghi', 4)").unwrap();
+
+        let updated = nimbus
+            .eval("update tbl1 set two = 20 where one = 'abc'")
+            .unwrap();
+        assert!(matches!(updated, NimbusExecuteResult::ChangeResult(1)));
+
+        let deleted = nimbus.eval("delete from tbl1 where two > 3").unwrap();
+        assert!(matches!(deleted, NimbusExecuteResult::ChangeResult(2)));
+
+        let select = nimbus.eval("select * from tbl1").unwrap();
+        let NimbusExecuteResult::SelectResult(rows) = select else {
+            panic!("expected a SelectResult");
+        };
+        assert_eq!(
+            rows,
+            vec![vec![
+                Literal::String("'def'".to_string()),
+                Literal::Numeric("3".to_string()),
+            ]]
+        );
+    }
+
+    #[test]
+    fn eval_with_params_binds_positional_placeholder() {
+        let mut nimbus = Nimbus::new();
+        nimbus.eval("create table tbl1(one text, two int)").unwrap();
+        nimbus
+            .eval_with_params(
+                "insert into tbl1 values (?, ?)",
+                &Params::positional(vec![
+                    Literal::String("'abc'".to_string()),
+                    Literal::Numeric("2".to_string()),
+                ]),
+            )
+            .unwrap();
+        let select = nimbus
+            .eval_with_params(
+                "select * from tbl1 where one = ?",
+                &Params::positional(vec![Literal::String("'abc'".to_string())]),
+            )
+            .unwrap();
+        assert_debug_snapshot!(select);
+    }
+
+    #[test]
+    fn session_records_changeset_that_can_be_inverted_and_applied() {
+        let mut nimbus = Nimbus::new();
+        nimbus.eval("create table tbl1(one text, two int)").unwrap();
+        nimbus.eval("insert into tbl1 values ('abc', 2)").unwrap();
+
+        nimbus.start_session();
+        nimbus.eval("insert into tbl1 values ('def', 3)").unwrap();
+        nimbus.eval("update tbl1 set two = 20 where one = 'abc'").unwrap();
+        nimbus.eval("delete from tbl1 where one = 'def'").unwrap();
+
+        let changeset = nimbus.session().unwrap().changeset();
+        assert_eq!(changeset.changes.len(), 3);
+
+        nimbus.apply_changeset(&changeset.invert()).unwrap();
+        let select = nimbus.eval("select * from tbl1").unwrap();
+        assert_debug_snapshot!(select);
+    }
+
+    #[test]
+    fn trace_and_update_hooks_fire_for_executed_statements() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let mut nimbus = Nimbus::new();
+        let traced = Rc::new(RefCell::new(vec![]));
+        let traced_handle = traced.clone();
+        nimbus.set_trace(move |sql| traced_handle.borrow_mut().push(sql.to_string()));
+
+        let updates = Rc::new(RefCell::new(vec![]));
+        let updates_handle = updates.clone();
+        nimbus.set_update_hook(move |action, table, row_index| {
+            updates_handle
+                .borrow_mut()
+                .push((action, table.to_string(), row_index));
+        });
+
+        nimbus.eval("create table tbl1(one text, two int)").unwrap();
+        nimbus.eval("insert into tbl1 values ('abc', 2)").unwrap();
+        nimbus.eval("update tbl1 set two = 3 where one = 'abc'").unwrap();
+
+        assert_eq!(traced.borrow().len(), 3);
+        assert_eq!(
+            *updates.borrow(),
+            vec![
+                (Action::Insert, "tbl1".to_string(), 0),
+                (Action::Update, "tbl1".to_string(), 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn save_and_load_round_trips_tables_and_rows() {
+        let path = std::env::temp_dir().join("nimbus_save_and_load_round_trips_tables_and_rows.sql");
+
+        let mut nimbus = Nimbus::new();
+        nimbus.eval("create table tbl1(one text, two int)").unwrap();
+        nimbus.eval("insert into tbl1 values ('abc', 2)").unwrap();
+        nimbus.eval("insert into tbl1 values ('def', 3)").unwrap();
+        nimbus.save(&path).unwrap();
+
+        let mut loaded = Nimbus::load(&path).unwrap();
+        let select = loaded.eval("select * from tbl1").unwrap();
+        assert_debug_snapshot!(select);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn save_and_load_round_trips_text_containing_embedded_newlines() {
+        let path = std::env::temp_dir()
+            .join("nimbus_save_and_load_round_trips_text_containing_embedded_newlines.sql");
+
+        let mut nimbus = Nimbus::new();
+        nimbus.eval("create table tbl1(one text)").unwrap();
+        nimbus.eval("insert into tbl1 values ('a\nb')").unwrap();
+        nimbus.save(&path).unwrap();
+
+        let mut loaded = Nimbus::load(&path).unwrap();
+        let select = loaded.eval("select * from tbl1").unwrap();
+        let NimbusExecuteResult::SelectResult(rows) = select else {
+            panic!("expected a SelectResult");
+        };
+        assert_eq!(rows, vec![vec![Literal::String("'a\nb'".to_string())]]);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn csv_virtual_table_imports_rows_with_inferred_types() {
+        let path = std::env::temp_dir().join("nimbus_csv_virtual_table_imports_rows_with_inferred_types.csv");
+        std::fs::write(&path, "name,age\nalice,30\nbob,\n").unwrap();
+
+        let mut nimbus = Nimbus::new();
+        nimbus
+            .eval(&format!(
+                "create virtual table people using csv(filename='{}', header='true')",
+                path.display()
+            ))
+            .unwrap();
+
+        let select = nimbus.eval("select * from people").unwrap();
+        let NimbusExecuteResult::SelectResult(rows) = select else {
+            panic!("expected a SelectResult");
+        };
+        assert_eq!(
+            rows,
+            vec![
+                vec![
+                    Literal::String("'alice'".to_string()),
+                    Literal::Numeric("30".to_string()),
+                ],
+                vec![Literal::String("'bob'".to_string()), Literal::Null],
+            ]
+        );
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
     #[test]
     fn t1() {
         let mut nimbus = Nimbus::new();
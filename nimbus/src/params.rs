@@ -0,0 +1,60 @@
+use anyhow::anyhow;
+use sqlite3_parser::ast::Literal;
+use std::cell::Cell;
+use std::collections::HashMap;
+
+/// Bound values for a statement's `?`, `?NNN`, and `:name`/`@name`/`$name`
+/// placeholders (the `Expr::Variable` nodes `sqlite3_parser` produces).
+/// Built with either `Params::positional` or `Params::named`; a bare `?` or
+/// `?NNN` resolves against the positional values, anything else against the
+/// named ones (keyed without the leading sigil).
+#[derive(Debug, Default)]
+pub struct Params {
+    positional: Vec<Literal>,
+    named: HashMap<String, Literal>,
+    next_auto_index: Cell<usize>,
+}
+
+impl Params {
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    pub fn positional(values: Vec<Literal>) -> Self {
+        Self {
+            positional: values,
+            ..Self::default()
+        }
+    }
+
+    pub fn named(values: HashMap<String, Literal>) -> Self {
+        Self {
+            named: values,
+            ..Self::default()
+        }
+    }
+
+    /// Resolve the raw text of an `Expr::Variable` (e.g. `?`, `?2`, `:name`)
+    /// to its bound value.
+    pub(crate) fn resolve(&self, raw: &str) -> anyhow::Result<Literal> {
+        let rest = raw.trim_start_matches(['?', ':', '@', '$']);
+        if rest.is_empty() {
+            let idx = self.next_auto_index.get();
+            self.next_auto_index.set(idx + 1);
+            self.positional
+                .get(idx)
+                .cloned()
+                .ok_or_else(|| anyhow!("no value bound for parameter ?{}", idx + 1))
+        } else if let Ok(n) = rest.parse::<usize>() {
+            self.positional
+                .get(n.saturating_sub(1))
+                .cloned()
+                .ok_or_else(|| anyhow!("no value bound for parameter ?{n}"))
+        } else {
+            self.named
+                .get(rest)
+                .cloned()
+                .ok_or_else(|| anyhow!("no value bound for parameter {raw}"))
+        }
+    }
+}
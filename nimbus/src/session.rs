@@ -0,0 +1,71 @@
+use sqlite3_parser::ast::Literal;
+
+/// The kind of row-level mutation a [`Change`] records, modeled on SQLite's
+/// session extension (`SQLITE_INSERT`/`SQLITE_UPDATE`/`SQLITE_DELETE`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeOp {
+    Insert,
+    Update,
+    Delete,
+}
+
+/// A single recorded row mutation: INSERT carries only `new_row`, DELETE
+/// only `old_row`, and UPDATE both.
+#[derive(Debug, Clone)]
+pub struct Change {
+    pub table_name: String,
+    pub op: ChangeOp,
+    pub old_row: Option<Vec<Literal>>,
+    pub new_row: Option<Vec<Literal>>,
+}
+
+/// An active recording of every row-level mutation performed while it's
+/// attached to a [`crate::Nimbus`] (via [`crate::Nimbus::start_session`]).
+#[derive(Debug, Default)]
+pub struct Session {
+    changes: Vec<Change>,
+}
+
+impl Session {
+    pub(crate) fn record(&mut self, change: Change) {
+        self.changes.push(change);
+    }
+
+    /// Snapshot the changes recorded so far.
+    pub fn changeset(&self) -> Changeset {
+        Changeset {
+            changes: self.changes.clone(),
+        }
+    }
+}
+
+/// An ordered, replayable (or invertible) sequence of row mutations.
+#[derive(Debug, Clone, Default)]
+pub struct Changeset {
+    pub changes: Vec<Change>,
+}
+
+impl Changeset {
+    /// Swap `old_row`/`new_row` and flip INSERT/DELETE (UPDATE stays an
+    /// UPDATE) on every change, in reverse order, producing the changeset
+    /// that undoes this one.
+    pub fn invert(&self) -> Changeset {
+        Changeset {
+            changes: self
+                .changes
+                .iter()
+                .rev()
+                .map(|change| Change {
+                    table_name: change.table_name.clone(),
+                    op: match change.op {
+                        ChangeOp::Insert => ChangeOp::Delete,
+                        ChangeOp::Delete => ChangeOp::Insert,
+                        ChangeOp::Update => ChangeOp::Update,
+                    },
+                    old_row: change.new_row.clone(),
+                    new_row: change.old_row.clone(),
+                })
+                .collect(),
+        }
+    }
+}
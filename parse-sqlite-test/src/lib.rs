@@ -1,10 +1,8 @@
 use crate::NodeKind::{Catch, DoTest, ExecSql, ResultLiteral, SqlLiteral};
-use crate::State::Parsing;
 use crate::ValueKind::String_;
 use anyhow::{anyhow, bail};
 use log::trace;
 use std::cmp::PartialEq;
-use std::collections::VecDeque;
 
 #[derive(PartialEq, Debug, Clone)]
 enum NodeKind {
@@ -36,207 +34,257 @@ impl Node {
             children: None,
         }
     }
-}
 
-impl Node {
-    fn add_child(&mut self, node: Node) {
-        if self.children.is_none() {
-            self.children = Some(vec![node]);
-        } else {
-            self.children.as_mut().unwrap().push(node);
+    fn mk_parent(kind: NodeKind, value: Option<ValueKind>, children: Vec<Node>) -> Self {
+        Self {
+            kind,
+            value,
+            children: Some(children),
         }
     }
 }
 
-#[derive(PartialEq, Debug)]
-enum State {
-    Toplevel,
-    Parsing(Node),
+/// A single Tcl word, as produced by [`tcl_words`]. The delimiter that
+/// introduced a word is kept (rather than discarded once braces/brackets are
+/// stripped) because `do_test`/`catch` dispatch below needs to tell a literal
+/// apart from a bareword or a `[command substitution]`.
+#[derive(Debug, Clone, PartialEq)]
+enum TclWord {
+    Plain(String),
+    Braced(String),
+    Bracketed(String),
 }
 
-impl State {
-    fn is_toplevel(&self) -> bool {
-        matches!(self, State::Toplevel)
+impl TclWord {
+    fn text(&self) -> &str {
+        match self {
+            TclWord::Plain(s) | TclWord::Braced(s) | TclWord::Bracketed(s) => s,
+        }
     }
 
-    fn mk_state(kind: NodeKind) -> Self {
-        Parsing(Node {
-            kind,
-            value: None,
-            children: None,
-        })
+    fn is_plain(&self, expected: &str) -> bool {
+        matches!(self, TclWord::Plain(s) if s == expected)
     }
+}
 
-    fn set_value(&mut self, value: ValueKind) -> anyhow::Result<()> {
-        match self {
-            State::Toplevel => {
-                bail!("cannot set value on top-level")
-            }
-            Parsing(node) => {
-                node.value = Some(value);
-                Ok(())
+/// Split `src` into top-level Tcl commands, one per logical statement. A
+/// newline only ends a command when brace nesting is balanced, so a
+/// `do_test name { ... }` body spanning many lines (and itself containing
+/// nested `{}`) stays a single command instead of being cut at the first
+/// embedded newline.
+fn split_top_level_commands(src: &str) -> Vec<String> {
+    let mut commands = vec![];
+    let mut current = String::new();
+    let mut depth: i32 = 0;
+    let mut chars = src.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if depth == 0 && c == '#' && current.trim().is_empty() {
+            for c in chars.by_ref() {
+                if c == '\n' {
+                    break;
+                }
             }
+            continue;
         }
-    }
-
-    fn add_child(&mut self, node: Node) -> anyhow::Result<()> {
-        match self {
-            State::Toplevel => {
-                bail!("cannot add child to top-level")
+        match c {
+            '{' => {
+                depth += 1;
+                current.push(c);
             }
-            Parsing(parent) => {
-                parent.add_child(node);
-                Ok(())
+            '}' => {
+                depth -= 1;
+                current.push(c);
             }
+            '\n' if depth <= 0 => {
+                if !current.trim().is_empty() {
+                    commands.push(std::mem::take(&mut current));
+                } else {
+                    current.clear();
+                }
+            }
+            _ => current.push(c),
         }
     }
+    if !current.trim().is_empty() {
+        commands.push(current);
+    }
+    commands
+}
 
-    fn get_node(&mut self) -> anyhow::Result<Node> {
-        if let Parsing(node) = self {
-            Ok(node.to_owned())
-        } else {
-            bail!("cannot get node from top-level")
+/// Read an `open`...`close`-delimited group (`{}` or `[]`) starting at
+/// `chars[start]`, honoring nested `open`/`close` pairs, and return its inner
+/// text (delimiters stripped) plus the index just past the matching close.
+fn read_group(chars: &[char], start: usize, open: char, close: char) -> anyhow::Result<(String, usize)> {
+    let mut depth = 0;
+    let mut i = start;
+    let inner_start = start + 1;
+    loop {
+        let c = *chars.get(i).ok_or_else(|| anyhow!("unterminated {open}...{close} group"))?;
+        if c == open {
+            depth += 1;
+        } else if c == close {
+            depth -= 1;
+            if depth == 0 {
+                return Ok((chars[inner_start..i].iter().collect(), i + 1));
+            }
         }
+        i += 1;
     }
 }
 
-fn parse(src: &str) -> anyhow::Result<Vec<Node>> {
-    let mut state = State::Toplevel;
-
-    struct Lines {
-        lines: VecDeque<String>,
+/// Tokenize a single Tcl command into words, respecting `{braced}` and
+/// `[bracketed]` groups as atomic words (with nesting), rather than slicing
+/// on hardcoded marker strings.
+fn tcl_words(cmd: &str) -> anyhow::Result<Vec<TclWord>> {
+    let chars: Vec<char> = cmd.chars().collect();
+    let mut words = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        while i < chars.len() && chars[i].is_whitespace() {
+            i += 1;
+        }
+        if i >= chars.len() {
+            break;
+        }
+        match chars[i] {
+            '{' => {
+                let (inner, next) = read_group(&chars, i, '{', '}')?;
+                words.push(TclWord::Braced(inner));
+                i = next;
+            }
+            '[' => {
+                let (inner, next) = read_group(&chars, i, '[', ']')?;
+                words.push(TclWord::Bracketed(inner));
+                i = next;
+            }
+            _ => {
+                let start = i;
+                while i < chars.len() && !chars[i].is_whitespace() {
+                    i += 1;
+                }
+                words.push(TclWord::Plain(chars[start..i].iter().collect()));
+            }
+        }
     }
+    Ok(words)
+}
 
-    impl Lines {
-        fn new(src: &str) -> Self {
-            let lines = src.split("\n");
-            let lines: Vec<_> = lines.map(str::to_string).collect();
-            let lines = VecDeque::from(lines);
-            Self { lines }
+/// Parse one `execsql`/`catchsql` command's argument into a SQL literal, or
+/// `None` if `words` isn't one of those forms.
+fn sql_literal(words: &[TclWord]) -> anyhow::Result<Option<(String, bool)>> {
+    let Some(name) = words.first() else {
+        return Ok(None);
+    };
+    match name {
+        TclWord::Plain(name) if name == "execsql" => {
+            let sql = words.get(1).ok_or_else(|| anyhow!("execsql missing sql body"))?;
+            Ok(Some((sql.text().to_string(), false)))
         }
-
-        fn next(&mut self) -> Option<String> {
-            let line = self.lines.pop_front();
-            Some(line?.trim().to_string())
+        TclWord::Plain(name) if name == "catchsql" => {
+            let sql = words.get(1).ok_or_else(|| anyhow!("catchsql missing sql body"))?;
+            Ok(Some((sql.text().to_string(), true)))
         }
+        _ => Ok(None),
     }
+}
 
-    let mut nodes = vec![];
-    let mut lines = Lines::new(src);
-    while let Some(line) = lines.next() {
-        let line = line.trim();
-        if line.starts_with("#")
-            || line.is_empty()
-            || (state.is_toplevel() && (line.starts_with("set") || line.starts_with("source")))
-        {
-            trace!("skipping {line}");
+/// Parse a `do_test` body (the script between its outer `{}`), collecting
+/// every `execsql`/`catchsql` statement it runs (there may be several) and
+/// whether any of them ran under `catch`. `set v [catch {execsql {...}} msg]`
+/// and the `lappend v $msg` line that sometimes follows it are unwrapped to
+/// the same effect as a bare `catchsql`.
+fn parse_do_test_body(body: &str) -> anyhow::Result<(Vec<String>, bool)> {
+    let mut sql_fragments = vec![];
+    let mut catch = false;
+
+    for command in split_top_level_commands(body) {
+        let words = tcl_words(&command)?;
+        if let Some((sql, is_catch)) = sql_literal(&words)? {
+            sql_fragments.push(sql);
+            catch |= is_catch;
             continue;
         }
 
-        if line.starts_with("do_test") {
-            state = State::mk_state(DoTest);
-            let line = line.replace("do_test ", "");
-            if let Some(idx) = line.find("{") {
-                let ident = &line[0..idx - 1].to_string();
-                state.set_value(String_(ident.to_string()))?;
-
-                if let Some(line) = lines.next() {
-                    const START_CATCH: &str = "set v [catch {execsql {";
-                    const END_CATCH: &str = "}} msg]";
-
-                    const START_SQL: &str = "execsql {";
-                    const END_SQL: &str = "}";
-
-                    if let Some(idx_start) = line.find(START_CATCH) {
-                        if let Some(idx_end) = line.find(END_CATCH) {
-                            state.add_child(Node::mk_node(Catch, ValueKind::Bool_(true)))?;
-                            let sql = line[idx_start + START_CATCH.len()..idx_end].to_string();
-                            state.add_child(Node::mk_node(SqlLiteral, String_(sql)))?;
-                            if !line[idx_end + END_CATCH.len()..line.len()].is_empty() {
-                                bail!(
-                                    "unexpected trailing chars: {}",
-                                    line[idx_end..line.len()].to_string()
-                                )
-                            }
-                        } else {
-                            bail!("expected {END_CATCH}")
-                        }
-                    } else if let Some(idx_start) = line.find(START_SQL) {
-                        if let Some(idx_end) = line.rfind(END_SQL) {
-                            let sql = line[idx_start + START_SQL.len()..idx_end].to_string();
-                            state.add_child(Node::mk_node(SqlLiteral, String_(sql)))?;
-                            if !line[idx_end + END_SQL.len()..line.len()].is_empty() {
-                                bail!(
-                                    "unexpected trailing chars: {}",
-                                    line[idx_end..line.len()].to_string()
-                                )
-                            }
-                        } else {
-                            let next_line = lines.next();
-                            if next_line.is_none() {
-                                bail!("expected line")
-                            }
-                            let line = [line, next_line.unwrap()].join(" ");
-                            if let Some(idx_end) = line.rfind(END_SQL) {
-                                let sql = line[idx_start + START_SQL.len()..idx_end].to_string();
-                                state.add_child(Node::mk_node(SqlLiteral, String_(sql)))?;
-                                if !line[idx_end + END_SQL.len()..line.len()].is_empty() {
-                                    bail!(
-                                        "unexpected trailing chars: {}",
-                                        line[idx_end..line.len()].to_string()
-                                    )
-                                }
-                            } else {
-                                bail!("expected {END_SQL}")
-                            }
-                        }
-                    } else {
-                        bail!("expected {START_CATCH} or {START_SQL}")
-                    }
-
-                    if let Some(mut line) = lines.next() {
-                        if line == "lappend v $msg" {
-                            line = lines.next().ok_or(anyhow!("expected line"))?;
-                        }
-
-                        if line.starts_with("}") {
-                            let line = line[1..line.len()].trim().to_string();
-                            if line.starts_with("{") && line.ends_with("}") {
-                                let result = line[1..line.len() - 1].to_string();
-                                state.add_child(Node::mk_node(ResultLiteral, String_(result)))?;
-                            } else {
-                                bail!("expected result")
-                            }
-                        } else {
-                            bail!("unexpected line {line}")
-                        }
-                    } else {
-                        bail!("expected line");
-                    }
-                } else {
-                    bail!("expected line")
+        match words.as_slice() {
+            [name, var, TclWord::Bracketed(inner)] if name.is_plain("set") && var.is_plain("v") => {
+                let inner_words = tcl_words(inner)?;
+                if !matches!(inner_words.first(), Some(w) if w.is_plain("catch")) {
+                    bail!("unsupported set-v form: {command}");
+                }
+                let inner_cmd_words = match inner_words.get(1) {
+                    Some(TclWord::Braced(inner)) => tcl_words(inner)?,
+                    _ => bail!("expected braced command after catch"),
                 };
-            } else {
-                bail!("expected '{{'")
+                let (sql, _) = sql_literal(&inner_cmd_words)?
+                    .ok_or_else(|| anyhow!("unsupported catch body: {command}"))?;
+                sql_fragments.push(sql);
+                catch = true;
+            }
+            [name, ..] if name.is_plain("lappend") => {
+                trace!("skipping {command}");
             }
-        } else if line.starts_with("execsql {") {
-            let line = line.replace("execsql {", "");
-            if let Some(idx) = line.rfind("}") {
-                let sql = line[0..idx].to_string();
-                state = State::mk_state(ExecSql);
-                state.set_value(String_(sql))?;
+            _ => {
+                trace!("skipping unrecognized do_test body command: {command}");
             }
-        } else if state.is_toplevel() {
-            bail!("could not parse")
         }
+    }
 
-        if !state.is_toplevel() {
-            trace!("{state:#?}");
-            nodes.push(state.get_node()?);
-            state = State::Toplevel;
-        }
+    Ok((sql_fragments, catch))
+}
+
+fn parse_do_test(words: &[TclWord]) -> anyhow::Result<Node> {
+    let name = words
+        .get(1)
+        .ok_or_else(|| anyhow!("do_test missing name"))?
+        .text()
+        .to_string();
+    let body = match words.get(2) {
+        Some(TclWord::Braced(body)) => body,
+        _ => bail!("do_test missing braced body"),
+    };
+    let expected = words
+        .get(3)
+        .ok_or_else(|| anyhow!("do_test missing expected result"))?
+        .text()
+        .to_string();
+
+    let (sql_fragments, catch) = parse_do_test_body(body)?;
+    if sql_fragments.is_empty() {
+        bail!("do_test {name} has no execsql/catchsql statements");
+    }
+
+    let mut children = vec![];
+    if catch {
+        children.push(Node::mk_node(Catch, ValueKind::Bool_(true)));
     }
+    children.push(Node::mk_node(SqlLiteral, String_(sql_fragments.join("\n"))));
+    children.push(Node::mk_node(ResultLiteral, String_(expected)));
+
+    Ok(Node::mk_parent(DoTest, Some(String_(name)), children))
+}
 
+/// Parse a full `.test` script into its `do_test`/`execsql` nodes,
+/// tracking brace nesting across lines so multi-line SQL bodies, nested
+/// `{}`, `catchsql`, and multiple statements inside one `do_test` all parse
+/// correctly. Directives this crate doesn't model (`set`, `source`, `proc`,
+/// `finish_test`, ...) are skipped rather than treated as parse errors, so
+/// the whole file is consumed instead of a hand-picked prefix.
+fn parse(src: &str) -> anyhow::Result<Vec<Node>> {
+    let mut nodes = vec![];
+    for command in split_top_level_commands(src) {
+        let words = tcl_words(&command)?;
+        let Some(name) = words.first() else {
+            continue;
+        };
+        if name.is_plain("do_test") {
+            nodes.push(parse_do_test(&words)?);
+        } else if let Some((sql, _catch)) = sql_literal(&words)? {
+            nodes.push(Node::mk_node(ExecSql, String_(sql)));
+        } else {
+            trace!("skipping {command}");
+        }
+    }
     Ok(nodes)
 }
 
@@ -330,10 +378,7 @@ pub mod sqlite_test_suite {
 
         pub fn script() -> Vec<SqliteTestStatement> {
             let select1 = include_str!("../../sqlite/test/select1.test");
-            let end_idx = select1
-                .find("set long {This is a string that is too big to fit inside a NBFS buffer}")
-                .unwrap();
-            let ast = parse(&select1[0..end_idx]).unwrap();
+            let ast = parse(select1).unwrap();
             ast.iter()
                 .map(|node| node.clone().try_into().unwrap())
                 .collect()
@@ -345,7 +390,7 @@ pub mod sqlite_test_suite {
 mod tests {
     use crate::NodeKind::{DoTest, SqlLiteral};
     use crate::ValueKind::String_;
-    use crate::{parse, Node, NodeKind};
+    use crate::{parse, Node, NodeKind, SqliteTestStatement};
     use ctor::ctor;
 
     #[ctor]
@@ -389,9 +434,73 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_do_test_body_with_multiple_statements() {
+        let expected_sql = "CREATE TABLE t2(a,b)\nSELECT a FROM t2";
+        let node = parse(
+            r#"do_test select1-2.1 {
+  execsql {CREATE TABLE t2(a,b)}
+  execsql {SELECT a FROM t2}
+} {}"#,
+        )
+        .unwrap();
+        let node = node.first().unwrap();
+        let sql = node
+            .children
+            .as_ref()
+            .unwrap()
+            .iter()
+            .find(|n| n.kind == SqlLiteral)
+            .unwrap();
+        assert_eq!(sql.value, Some(String_(expected_sql.to_string())));
+    }
+
+    #[test]
+    fn test_do_test_with_catch() {
+        let node = parse(
+            r#"do_test select1-3.1 {
+  set v [catch {execsql {SELECT * FROM nosuchtable}} msg]
+  lappend v $msg
+} {1 {no such table: nosuchtable}}"#,
+        )
+        .unwrap();
+        let node = node.first().unwrap();
+        let children = node.children.as_ref().unwrap();
+        assert!(children.iter().any(|n| n.kind == NodeKind::Catch));
+        let sql = children.iter().find(|n| n.kind == SqlLiteral).unwrap();
+        assert_eq!(
+            sql.value,
+            Some(String_("SELECT * FROM nosuchtable".to_string()))
+        );
+    }
+
     #[test]
     fn test_select1() {
         let script = super::sqlite_test_suite::select1::script();
-        assert_eq!(script.len(), 24);
+        let test_names: Vec<&str> = script
+            .iter()
+            .filter_map(|stmt| match stmt {
+                SqliteTestStatement::Test { name, .. } => Some(name.as_str()),
+                _ => None,
+            })
+            .collect();
+
+        // Spot-check do_test names from the opening sections of the suite
+        // (select1-1.x through select1-3.x) so a command the catch-all
+        // silently skips (dropping one of these do_tests from the parsed
+        // script) fails the test, instead of slipping past a bare length
+        // check.
+        for expected in [
+            "select1-1.0",
+            "select1-1.1",
+            "select1-1.4",
+            "select1-2.1",
+            "select1-3.1",
+        ] {
+            assert!(
+                test_names.contains(&expected),
+                "expected do_test {expected} in select1.test, got {test_names:?}"
+            );
+        }
     }
 }